@@ -1,6 +1,7 @@
 use std::{
+	any::TypeId,
 	collections::BTreeMap,
-	fmt::Debug,
+	fmt::{self, Debug, Formatter},
 	marker::PhantomData,
 	ops::{Deref, DerefMut},
 };
@@ -9,14 +10,15 @@ use crate::{
 	composition::loaded::loaded_crate::LoadedCrate,
 	concurrency::access::{Access, AccessType},
 	identify::{crate_name::CrateName, datachunk_name::FullDatachunkName},
-	user_types::datachunk::Datachunkable,
+	user_types::datachunk::{Datachunkable, Partitionable},
 	utils::mutable_arc::MutableArc,
 };
 
+/// `A` is the datachunk type, or the partition type for [get_partition_immut](DatachunkGetter::get_partition_immut) and [get_partition_mut](DatachunkGetter::get_partition_mut).
 #[derive(Clone, Debug)]
 pub enum DatachunkGetterResult<T, A>
 where
-	A: Datachunkable,
+	A: ?Sized,
 	T: Deref<Target = A>,
 {
 	Ok(T),
@@ -25,12 +27,16 @@ where
 	NoImmutableAccessAllowed,
 	NoMutableAccessAllowed,
 	WrongType,
+	/// The datachunk's type did not recognize the partition name.
+	PartitionNotFound,
 }
 
-impl<'a, Mutability: Debug, A: Datachunkable>
-	DatachunkGetterResult<DatachunkWrapper<Mutability, A>, A>
+impl<T, A> DatachunkGetterResult<T, A>
+where
+	A: ?Sized + Debug,
+	T: Deref<Target = A> + Debug,
 {
-	pub fn or_panic(self) -> DatachunkWrapper<Mutability, A> {
+	pub fn or_panic(self) -> T {
 		if let Self::Ok(v) = self {
 			return v;
 		}
@@ -54,15 +60,19 @@ impl DatachunkGetter {
 		}
 	}
 
+	/// An access to a whole datachunk also allows access to each of its partitions, but an access to a partition allows no other.
 	fn check_access(
 		&self,
 		name: &FullDatachunkName,
+		partition: Option<&str>,
 		mutable: AccessType,
 	) -> bool {
 		let mut found = false;
 
 		for access in &self.accesses {
 			if &access.of == name
+				&& (access.partition.is_none()
+					|| access.partition.as_deref() == partition)
 				&& match mutable {
 					AccessType::ImmutableAccess => true,
 					AccessType::MutableAccess => {
@@ -76,11 +86,10 @@ impl DatachunkGetter {
 		found
 	}
 
-	fn get<Mutability, T: Datachunkable>(
+	fn resolve<Mutability, T: Datachunkable>(
 		&self,
 		name: &FullDatachunkName,
-	) -> DatachunkGetterResult<DatachunkWrapper<Mutability, T>, T> {
-		println!("crate table: {:#?}", self.crate_table);
+	) -> DatachunkGetterResult<DatachunkHandle<Mutability, T>, T> {
 		match self.crate_table.get().get(&name.crate_name) {
 			Some(loaded_crate) => {
 				match loaded_crate.datachunks.get(&name.datachunk_name) {
@@ -95,13 +104,13 @@ impl DatachunkGetter {
 								as *mut dyn Datachunkable))
 								.downcast_mut::<T>()
 							{
-								Some(v) => v,
+								Some(v) => v as *mut T,
 								None => {
 									return DatachunkGetterResult::WrongType
 								}
 							}
 						};
-						return DatachunkGetterResult::Ok(DatachunkWrapper {
+						return DatachunkGetterResult::Ok(DatachunkHandle {
 							phantom: PhantomData::default(),
 							inner: v,
 							_preserve_lifetime: dyn_object,
@@ -118,11 +127,44 @@ impl DatachunkGetter {
 		}
 	}
 
+	fn get<Mutability, T: Datachunkable>(
+		&self,
+		name: &FullDatachunkName,
+	) -> DatachunkGetterResult<DatachunkWrapper<Mutability, T>, T> {
+		match self.resolve::<Mutability, T>(name) {
+			DatachunkGetterResult::Ok(handle) => {
+				DatachunkGetterResult::Ok(DatachunkWrapper {
+					phantom: PhantomData::default(),
+					inner: unsafe { &mut *handle.inner },
+					_preserve_lifetime: handle._preserve_lifetime,
+				})
+			}
+			DatachunkGetterResult::CrateNotFound => {
+				DatachunkGetterResult::CrateNotFound
+			}
+			DatachunkGetterResult::DatachunkNotInCrate => {
+				DatachunkGetterResult::DatachunkNotInCrate
+			}
+			DatachunkGetterResult::NoImmutableAccessAllowed => {
+				DatachunkGetterResult::NoImmutableAccessAllowed
+			}
+			DatachunkGetterResult::NoMutableAccessAllowed => {
+				DatachunkGetterResult::NoMutableAccessAllowed
+			}
+			DatachunkGetterResult::WrongType => {
+				DatachunkGetterResult::WrongType
+			}
+			DatachunkGetterResult::PartitionNotFound => {
+				DatachunkGetterResult::PartitionNotFound
+			}
+		}
+	}
+
 	pub fn get_immut<T: Datachunkable>(
 		&self,
 		name: &FullDatachunkName,
 	) -> DatachunkGetterResult<DatachunkWrapper<Immutable, T>, T> {
-		if !self.check_access(name, AccessType::ImmutableAccess) {
+		if !self.check_access(name, None, AccessType::ImmutableAccess) {
 			return DatachunkGetterResult::NoImmutableAccessAllowed;
 		}
 		match self.get(name) {
@@ -142,6 +184,9 @@ impl DatachunkGetter {
 			DatachunkGetterResult::WrongType => {
 				DatachunkGetterResult::WrongType
 			}
+			DatachunkGetterResult::PartitionNotFound => {
+				DatachunkGetterResult::PartitionNotFound
+			}
 		}
 	}
 
@@ -149,11 +194,93 @@ impl DatachunkGetter {
 		&self,
 		name: &FullDatachunkName,
 	) -> DatachunkGetterResult<DatachunkWrapper<Mutable, T>, T> {
-		if !self.check_access(name, AccessType::MutableAccess) {
+		if !self.check_access(name, None, AccessType::MutableAccess) {
 			return DatachunkGetterResult::NoMutableAccessAllowed;
 		}
 		self.get(name)
 	}
+
+	/// Never borrows the whole datachunk, as tasks with other partitions of it may be running.
+	fn get_partition<Mutability, T: Partitionable>(
+		&self,
+		name: &FullDatachunkName,
+		partition: &str,
+	) -> DatachunkGetterResult<PartitionWrapper<Mutability, T>, T::Partition> {
+		let loaded_datachunk = match self
+			.crate_table
+			.get()
+			.get(&name.crate_name)
+			.map(|v| v.datachunks.get(&name.datachunk_name))
+		{
+			Some(Some(v)) => v.as_ref().unwrap(),
+			Some(None) => return DatachunkGetterResult::DatachunkNotInCrate,
+			None => return DatachunkGetterResult::CrateNotFound,
+		};
+		if loaded_datachunk.type_id != TypeId::of::<T>() {
+			return DatachunkGetterResult::WrongType;
+		}
+		let datachunk = loaded_datachunk.user_data.as_ptr() as *mut T;
+		match unsafe { T::partition_ptr(datachunk, partition) } {
+			Some(inner) => DatachunkGetterResult::Ok(PartitionWrapper {
+				phantom: PhantomData::default(),
+				inner,
+				partition: partition.to_owned(),
+				_preserve_lifetime: loaded_datachunk.user_data.clone(),
+			}),
+			None => DatachunkGetterResult::PartitionNotFound,
+		}
+	}
+
+	/// Get one partition of a datachunk this task may read, either through an access to the partition or to the whole datachunk.
+	pub fn get_partition_immut<T: Partitionable>(
+		&self,
+		name: &FullDatachunkName,
+		partition: &str,
+	) -> DatachunkGetterResult<PartitionWrapper<Immutable, T>, T::Partition> {
+		if !self.check_access(
+			name,
+			Some(partition),
+			AccessType::ImmutableAccess,
+		) {
+			return DatachunkGetterResult::NoImmutableAccessAllowed;
+		}
+		self.get_partition(name, partition)
+	}
+
+	/// Get one partition of a datachunk this task may write. Other tasks may be writing other partitions of the same datachunk meanwhile.
+	pub fn get_partition_mut<T: Partitionable>(
+		&self,
+		name: &FullDatachunkName,
+		partition: &str,
+	) -> DatachunkGetterResult<PartitionWrapper<Mutable, T>, T::Partition> {
+		if !self.check_access(name, Some(partition), AccessType::MutableAccess)
+		{
+			return DatachunkGetterResult::NoMutableAccessAllowed;
+		}
+		self.get_partition(name, partition)
+	}
+
+	/// Resolve a typed handle to a datachunk this task may read. Call this once when building the task closure in `Taskable::run`, so that a missing datachunk, undeclared access or wrong type is reported at load time; dereferencing the handle afterwards does no lookups.
+	pub fn resolve_immut<T: Datachunkable>(
+		&self,
+		name: &FullDatachunkName,
+	) -> DatachunkGetterResult<DatachunkHandle<Immutable, T>, T> {
+		if !self.check_access(name, None, AccessType::ImmutableAccess) {
+			return DatachunkGetterResult::NoImmutableAccessAllowed;
+		}
+		self.resolve(name)
+	}
+
+	/// Resolve a typed handle to a datachunk this task may write. See [resolve_immut](Self::resolve_immut).
+	pub fn resolve_mut<T: Datachunkable>(
+		&self,
+		name: &FullDatachunkName,
+	) -> DatachunkGetterResult<DatachunkHandle<Mutable, T>, T> {
+		if !self.check_access(name, None, AccessType::MutableAccess) {
+			return DatachunkGetterResult::NoMutableAccessAllowed;
+		}
+		self.resolve(name)
+	}
 }
 
 #[derive(Debug)]
@@ -181,3 +308,229 @@ impl<T: Datachunkable> DerefMut for DatachunkWrapper<Mutable, T> {
 		self.inner
 	}
 }
+
+/// A partition of a datachunk, from [DatachunkGetter::get_partition_immut] or [DatachunkGetter::get_partition_mut].
+pub struct PartitionWrapper<Mutability, T: Partitionable> {
+	phantom: PhantomData<Mutability>,
+	inner: *mut T::Partition,
+	partition: String,
+	_preserve_lifetime: MutableArc<dyn Datachunkable>, //make sure that the underlying data isn't dropped prematurely
+}
+
+impl<Mutability, T: Partitionable> PartitionWrapper<Mutability, T> {
+	pub fn partition_name(&self) -> &str {
+		self.partition.as_str()
+	}
+}
+
+impl<Mutability, T: Partitionable> Debug for PartitionWrapper<Mutability, T> {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("PartitionWrapper")
+			.field("datachunk", &std::any::type_name::<T>())
+			.field("partition", &self.partition)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<Mutability, T: Partitionable> Deref for PartitionWrapper<Mutability, T> {
+	type Target = T::Partition;
+	fn deref(&self) -> &T::Partition {
+		unsafe { &*self.inner }
+	}
+}
+
+impl<T: Partitionable> DerefMut for PartitionWrapper<Mutable, T> {
+	fn deref_mut(&mut self) -> &mut T::Partition {
+		unsafe { &mut *self.inner }
+	}
+}
+
+/// A datachunk resolved once by [DatachunkGetter::resolve_immut] or [DatachunkGetter::resolve_mut]. Dereferencing it is a plain pointer access.
+#[derive(Debug)]
+pub struct DatachunkHandle<Mutability, T: Datachunkable> {
+	phantom: PhantomData<Mutability>,
+	inner: *mut T,
+	_preserve_lifetime: MutableArc<dyn Datachunkable>, //make sure that the underlying data isn't dropped prematurely
+}
+
+unsafe impl<Mutability, T: Datachunkable> Send
+	for DatachunkHandle<Mutability, T>
+{
+}
+unsafe impl<Mutability, T: Datachunkable> Sync
+	for DatachunkHandle<Mutability, T>
+{
+}
+
+impl<T: Datachunkable> Clone for DatachunkHandle<Immutable, T> {
+	fn clone(&self) -> Self {
+		Self {
+			phantom: PhantomData::default(),
+			inner: self.inner,
+			_preserve_lifetime: self._preserve_lifetime.clone(),
+		}
+	}
+}
+
+impl<Mutability, T: Datachunkable> Deref for DatachunkHandle<Mutability, T> {
+	type Target = T;
+	fn deref(&self) -> &T {
+		unsafe { &*self.inner }
+	}
+}
+
+impl<T: Datachunkable> DerefMut for DatachunkHandle<Mutable, T> {
+	fn deref_mut(&mut self) -> &mut T {
+		unsafe { &mut *self.inner }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{DatachunkGetter, DatachunkGetterResult};
+
+	use crate::{
+		composition::{
+			loaded::{
+				loaded_crate::LoadedCrate, loaded_datachunk::LoadedDatachunk,
+			},
+			unloaded::unloaded_datachunk::UnloadedDatachunk,
+		},
+		concurrency::access::{Access, AccessType},
+		errors::custard_error::CustardError,
+		identify::{
+			crate_name::CrateName,
+			custard_name::CustardName,
+			datachunk_name::{DatachunkName, FullDatachunkName},
+		},
+		user_types::datachunk::{Datachunkable, Partitionable},
+		utils::mutable_arc::MutableArc,
+	};
+
+	use std::{collections::BTreeMap, ptr, sync::Arc};
+
+	#[derive(Debug)]
+	struct Particles {
+		left: Vec<u32>,
+		right: Vec<u32>,
+	}
+
+	impl Datachunkable for Particles {
+		fn is_partitionable(&self) -> bool {
+			true
+		}
+	}
+
+	unsafe impl Partitionable for Particles {
+		type Partition = Vec<u32>;
+
+		unsafe fn partition_ptr(
+			this: *mut Self,
+			name: &str,
+		) -> Option<*mut Vec<u32>> {
+			match name {
+				"left" => Some(ptr::addr_of_mut!((*this).left)),
+				"right" => Some(ptr::addr_of_mut!((*this).right)),
+				_ => None,
+			}
+		}
+	}
+
+	#[derive(Debug)]
+	struct Unpartitioned;
+
+	impl Datachunkable for Unpartitioned {}
+
+	fn getter_for(accesses: Vec<Access>) -> DatachunkGetter {
+		let mut datachunks = BTreeMap::new();
+		datachunks.insert(
+			DatachunkName::new("particles".to_owned()),
+			Some(
+				LoadedDatachunk::from_object(
+					name(),
+					&UnloadedDatachunk::new("Particles", "particles.ron")
+						.partition("left")
+						.partition("right"),
+					MutableArc::new(Arc::new(Particles {
+						left: vec![0, 1],
+						right: vec![2, 3],
+					})),
+				)
+				.unwrap(),
+			),
+		);
+		let mut crate_table = BTreeMap::new();
+		crate_table.insert(
+			CrateName::new("sim".to_owned()),
+			LoadedCrate {
+				datachunks,
+				tasks: BTreeMap::new(),
+			},
+		);
+		DatachunkGetter::new(MutableArc::new(Arc::new(crate_table)), accesses)
+	}
+
+	fn access(partition: Option<&str>, mut_immut: AccessType) -> Access {
+		Access {
+			of: name(),
+			mut_immut,
+			type_name: None,
+			partition: partition.map(|v| v.to_owned()),
+		}
+	}
+
+	fn name() -> FullDatachunkName {
+		FullDatachunkName::new("sim".to_owned(), "particles".to_owned())
+	}
+
+	#[test]
+	fn partition_accesses_are_limited_to_their_partition() {
+		let getter =
+			getter_for(vec![access(Some("left"), AccessType::MutableAccess)]);
+		let mut left = getter
+			.get_partition_mut::<Particles>(&name(), "left")
+			.or_panic();
+		left[1] = 10;
+		assert!(matches!(
+			getter.get_partition_mut::<Particles>(&name(), "right"),
+			DatachunkGetterResult::NoMutableAccessAllowed
+		));
+		assert!(matches!(
+			getter.get_immut::<Particles>(&name()),
+			DatachunkGetterResult::NoImmutableAccessAllowed
+		));
+
+		let getter =
+			getter_for(vec![access(None, AccessType::ImmutableAccess)]);
+		assert_eq!(
+			*getter
+				.get_partition_immut::<Particles>(&name(), "right")
+				.or_panic(),
+			vec![2, 3]
+		);
+		assert!(matches!(
+			getter.get_partition_immut::<Particles>(&name(), "middle"),
+			DatachunkGetterResult::PartitionNotFound
+		));
+	}
+
+	#[test]
+	fn partitions_require_a_partitionable_type() {
+		let unloaded =
+			UnloadedDatachunk::new("Unpartitioned", "u.ron").partition("left");
+		assert!(matches!(
+			LoadedDatachunk::from_object(
+				name(),
+				&unloaded,
+				MutableArc::new(Arc::new(Unpartitioned)),
+			),
+			Err(CustardError::DatachunkNotPartitionable(_))
+		));
+		assert!(LoadedDatachunk::from_object(
+			name(),
+			&UnloadedDatachunk::new("Unpartitioned", "u.ron"),
+			MutableArc::new(Arc::new(Unpartitioned)),
+		)
+		.is_ok());
+	}
+}
@@ -5,41 +5,60 @@ use crate::{
 			loaded_task::LoadedTask,
 		},
 		unloaded::{
+			lint::{CompositionLint, LintKind, LintSubject},
+			task_edits::TaskEdits,
 			unloaded_composition::UnloadedComposition,
 			unloaded_task::UnloadedTask,
 		},
 	},
 	concurrency::{
+		access::AccessType,
+		executor::Executor,
 		fulfiller::{Fulfiller, Quit},
-		fulfiller_chain::FulfillerChain,
+		fulfiller_chain::{FulfillerChain, RunContext},
+		happens_before::HappensBefore,
 		possibly_poisoned_mutex::PossiblyPoisonedMutex,
+		scheduler::Scheduler,
+		work_stealing::WorkStealingScheduler,
 	},
-	dylib_management::safe_library::safe_library::{
-		DebugMode, LibraryRecompile,
+	dylib_management::safe_library::{
+		safe_library::{self, DebugMode, LibraryRecompile, SafeLibrary},
+		user_library::UserLibrary,
 	},
 	errors::{
-		datachunk_errors::custard_datachunk_access_error::CustardDatachunkAccessError,
+		custard_error::CustardError,
+		datachunk_errors::{
+			custard_datachunk_access_error::CustardDatachunkAccessError,
+			custard_datachunk_not_found_error::CustardDatachunkNotFoundError,
+			custard_datachunk_type_mismatch_error::CustardDatachunkTypeMismatchError,
+			custard_partition_not_found_error::CustardPartitionNotFoundError,
+		},
 		task_composition_errors::{
+			custard_disabled_task_writes_error::CustardDisabledTaskWritesError,
 			custard_not_in_cycle_error::CustardNotInCycleError,
+			custard_parent_not_found_error::CustardParentNotFoundError,
+			custard_task_not_found_error::CustardTaskNotFoundError,
+			custard_task_type_not_loaded_error::CustardTaskTypeNotLoadedError,
 			custard_unreachable_task_error::CustardUnreachableTaskError,
 		},
 	},
 	identify::{
 		crate_name::CrateName,
-		datachunk_name::DatachunkName,
+		custard_name::CustardName,
+		datachunk_name::{DatachunkName, FullDatachunkName},
 		task_name::{FullTaskName, TaskName},
 	},
 	instance_control_flow::InstanceControlFlow,
-	utils::mutable_arc::MutableArc,
+	recording::cycle_hook::{BoundCycleHook, CycleHook},
+	run_options::RunOptions,
+	utils::{edit_distance::closest_match, mutable_arc::MutableArc},
 };
 
 use log::info;
-use threadpool::ThreadPool;
 
 use std::{
 	cell::RefCell,
 	collections::{BTreeMap, BTreeSet},
-	error::Error,
 	rc::Rc,
 	sync::{Arc, Mutex, Weak},
 };
@@ -55,6 +74,8 @@ pub struct LoadedComposition {
 	pub(crate) fulfiller_chains: Arc<Vec<Arc<FulfillerChain>>>,
 	pub(crate) task_completion: Arc<Quit>,
 	pub(crate) control_flow: Arc<PossiblyPoisonedMutex<InstanceControlFlow>>,
+	/// Kept so that task edits and partial reloads can load new tasks from the same library copy as the tasks they run alongside. Keyed by the crate the library was built from, which differs from the names of crates listed with `instance_of`.
+	pub(crate) user_libraries: BTreeMap<CrateName, Rc<UserLibrary>>,
 }
 
 impl LoadedComposition {
@@ -65,7 +86,7 @@ impl LoadedComposition {
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
 		_checked: Checked,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
 		Self::new_with_baggage(
 			quit,
 			composition,
@@ -73,6 +94,7 @@ impl LoadedComposition {
 			debug,
 			drop_list,
 			_checked,
+			&mut BTreeMap::new(),
 			BTreeMap::new(),
 		)
 	}
@@ -84,14 +106,15 @@ impl LoadedComposition {
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
 		_checked: Checked,
-		mut old_crates: BTreeMap<
+		old_crates: &mut BTreeMap<
 			CrateName,
 			(
 				BTreeMap<TaskName, LoadedTask>,
 				BTreeMap<DatachunkName, LoadedDatachunk>,
 			),
 		>,
-	) -> Result<Self, Box<dyn Error>> {
+		old_libraries: BTreeMap<CrateName, Rc<UserLibrary>>,
+	) -> Result<Self, CustardError> {
 		let mut task_count = 0;
 		for (_, unloaded_crate_contents) in &composition.crates {
 			for (_, _) in &unloaded_crate_contents.tasks {
@@ -111,45 +134,165 @@ impl LoadedComposition {
 			control_flow: Arc::new(PossiblyPoisonedMutex::new(Mutex::new(
 				InstanceControlFlow::Continue,
 			))),
+			user_libraries: BTreeMap::new(),
 		};
+		let mut to_load = BTreeSet::new();
+		for (crate_name, unloaded_crate_contents) in &composition.crates {
+			let library_name = unloaded_crate_contents.library_name(crate_name);
+			if !old_libraries.contains_key(library_name)
+				&& !composition.built.contains(library_name)
+			{
+				to_load.insert(library_name.clone());
+			}
+		}
+		safe_library::compile_libraries(&to_load, &recompile, debug.clone())?;
+
+		let loaded = ret
+			.load_crates(
+				composition,
+				debug,
+				drop_list,
+				old_crates,
+				old_libraries,
+			)
+			.and_then(|_| {
+				ret.connect_fulfillers(composition)?;
+				ret.create_fulfiller_chains(composition)?;
+				ret.attach_fulfiller_chains()?;
+				ret.loaded_access_type_check(composition)?;
+				ret.load_closures(crate_table)
+			});
+		if let Err(e) = loaded {
+			//hand every task and datachunk back, so that the caller can return the reused ones to the composition they came from
+			for (crate_name, loaded_crate) in unsafe { ret.crates.get_mut() } {
+				let (tasks, datachunks) = loaded_crate.take_contents();
+				let old_crate =
+					old_crates.entry(crate_name.clone()).or_default();
+				old_crate.0.extend(tasks);
+				old_crate.1.extend(datachunks);
+			}
+			return Err(e);
+		}
+
+		Ok(ret)
+	}
+
+	fn load_crates(
+		&mut self,
+		composition: &UnloadedComposition,
+		debug: DebugMode,
+		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
+		old_crates: &mut BTreeMap<
+			CrateName,
+			(
+				BTreeMap<TaskName, LoadedTask>,
+				BTreeMap<DatachunkName, LoadedDatachunk>,
+			),
+		>,
+		mut old_libraries: BTreeMap<CrateName, Rc<UserLibrary>>,
+	) -> Result<(), CustardError> {
 		for (crate_name, unloaded_crate_contents) in &composition.crates {
 			let old_crate = old_crates.get_mut(crate_name);
 			if old_crate.is_some() {
 				info!("Matched crate {:?} to old crate, reusing.", crate_name);
 			}
+			let library_name = unloaded_crate_contents.library_name(crate_name);
+			let user_library = match self.user_libraries.get(library_name) {
+				Some(v) => v.clone(), //another instance of the same crate
+				None => match old_libraries.remove(library_name) {
+					Some(v) => v,
+					None => Rc::new(UserLibrary::new(
+						library_name.clone(),
+						LibraryRecompile::InsistCached, //compiled above
+						debug.clone(),
+						drop_list.clone(),
+					)?),
+				},
+			};
 			let loaded_crate_contents = LoadedCrate::new(
 				crate_name,
 				unloaded_crate_contents,
-				recompile.clone(),
-				debug.clone(),
-				drop_list.clone(),
+				&user_library,
 				old_crate,
 			)?;
+			self.user_libraries
+				.insert(library_name.clone(), user_library);
 			unsafe {
-				ret.crates
+				self.crates
 					.get_mut()
 					.insert(crate_name.clone(), loaded_crate_contents);
 			}
 		}
+		Ok(())
+	}
 
-		ret.connect_fulfillers(composition)?;
-		ret.create_fulfiller_chains(composition)?;
-		ret.attach_fulfiller_chains()?;
-
-		for (_crate_name, crate_contents) in ret.crates.get() {
-			for (_task_name, task_contents) in &crate_contents.tasks {
-				unsafe {
-					(&mut *(task_contents.task.as_ref().unwrap()
-						as *const LoadedTask as *mut LoadedTask))
-						.load_closure(crate_table.clone())?;
+	/// Check each access that states a type against the type its datachunk was actually loaded as. [check](Self::check) only compares it with the composition's `type_name`.
+	fn loaded_access_type_check(
+		&self,
+		composition: &UnloadedComposition,
+	) -> Result<(), CustardError> {
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				for access in &task_contents.accesses {
+					let expected_type = match &access.type_name {
+						Some(v) => v,
+						None => continue,
+					};
+					let datachunk = match self
+						.crates
+						.get()
+						.get(&access.of.crate_name)
+						.and_then(|v| {
+							v.datachunks.get(&access.of.datachunk_name)
+						}) {
+						Some(Some(v)) => v,
+						_ => continue,
+					};
+					let found_type =
+						datachunk.user_data.get().datachunk_type_name();
+					if !LoadedDatachunk::type_name_matches(
+						found_type,
+						expected_type,
+					) {
+						return Err(CustardDatachunkTypeMismatchError {
+							datachunk: access.of.clone(),
+							expected_by: Some(FullTaskName {
+								crate_name: crate_name.clone(),
+								task_name: task_name.clone(),
+							}),
+							expected_type: expected_type.clone(),
+							found_type: found_type.to_owned(),
+						}
+						.into());
+					}
 				}
 			}
 		}
+		Ok(())
+	}
 
-		Ok(ret)
+	/// Build every task's closure before storing any, so that a task panicking in `run` leaves the others as they were.
+	fn load_closures(
+		&self,
+		crate_table: MutableArc<BTreeMap<CrateName, LoadedCrate>>,
+	) -> Result<(), CustardError> {
+		let mut closures = vec![];
+		for crate_contents in self.crates.get().values() {
+			for task_contents in crate_contents.tasks.values() {
+				let task = task_contents.task.as_ref().unwrap();
+				closures.push((task, task.build_closure(crate_table.clone())?));
+			}
+		}
+		for (task, closure) in closures {
+			unsafe {
+				(*(task as *const LoadedTask as *mut LoadedTask)).closure =
+					Some(closure);
+			}
+		}
+		Ok(())
 	}
 
-	pub fn attach_fulfiller_chains(&mut self) -> Result<(), Box<dyn Error>> {
+	pub fn attach_fulfiller_chains(&mut self) -> Result<(), CustardError> {
 		info!("Attaching fulfiller chains to fulfillers.");
 		for chain in &*self.fulfiller_chains {
 			let first_node = self
@@ -174,57 +317,70 @@ impl LoadedComposition {
 
 	fn ancestor_check(
 		composition: &UnloadedComposition,
-	) -> Result<(), Box<dyn Error>> {
+	) -> Result<(), CustardError> {
 		info!("Checking connection rules for unloaded composition.");
 		for (crate_name, crate_contents) in &composition.crates {
 			for (task_name, _task_contents) in &crate_contents.tasks {
-				let mut found = false;
-				let mut entrypoint_exists = false;
+				Self::task_ancestor_check(
+					composition,
+					FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					},
+				)?;
+			}
+		}
+		info!("Success checking connection rules for unloaded composition.");
+		Ok(())
+	}
 
-				let mut traversal_list = vec![];
-				let start_node = FullTaskName {
-					crate_name: crate_name.clone(),
-					task_name: task_name.clone(),
-				};
-				composition.traverse_until(
-					&start_node,
-					&mut traversal_list,
-					Rc::new(RefCell::new(
-						|_current, contents: &UnloadedTask| -> bool {
-							if contents.entrypoint {
-								entrypoint_exists = true;
-							}
-							if contents.parents.contains(&start_node) {
-								found = true;
-								return true;
-							}
-							return false;
-						},
-					)),
-				);
+	fn task_ancestor_check(
+		composition: &UnloadedComposition,
+		start_node: FullTaskName,
+	) -> Result<(), CustardError> {
+		let mut found = false;
+		let mut entrypoint_exists = false;
 
-				if !found {
-					return Err(Box::new(CustardNotInCycleError {
-						offending_task: start_node.clone(),
-					}));
-				}
+		let mut traversal_list = vec![];
+		composition.traverse_until(
+			&start_node,
+			&mut traversal_list,
+			Rc::new(RefCell::new(
+				|_current, contents: &UnloadedTask| -> bool {
+					if contents.entrypoint {
+						entrypoint_exists = true;
+					}
+					if contents.parents.contains(&start_node) {
+						found = true;
+						return true;
+					}
+					return false;
+				},
+			)),
+		);
 
-				if !entrypoint_exists {
-					return Err(Box::new(CustardUnreachableTaskError {
-						offending_task: start_node.clone(),
-					}));
-				}
+		if !found {
+			return Err(CustardNotInCycleError {
+				offending_task: start_node.clone(),
 			}
+			.into());
+		}
+
+		if !entrypoint_exists {
+			return Err(CustardUnreachableTaskError {
+				offending_task: start_node.clone(),
+			}
+			.into());
 		}
-		info!("Success checking connection rules for unloaded composition.");
 		Ok(())
 	}
 
 	fn connect_fulfillers(
 		&mut self,
 		composition: &UnloadedComposition,
-	) -> Result<(), Box<dyn Error>> {
+	) -> Result<(), CustardError> {
 		info!("Connecting fulfiller shared references.");
+		let mut children = BTreeMap::new();
 		for (crate_name, unloaded_crate) in &composition.crates {
 			let loaded_crate = match self.crates.get().get(crate_name) {
 				Some(v) => v,
@@ -263,6 +419,24 @@ impl LoadedComposition {
 					*(&loaded_task.prerequisites as *const _
 						as *mut Vec<Weak<Fulfiller>>) = loaded_parents;
 				}
+
+				for parent_name in &unloaded_task.parents {
+					children
+						.entry(parent_name.clone())
+						.or_insert_with(Vec::new)
+						.push(Arc::downgrade(loaded_task));
+				}
+			}
+		}
+		for (crate_name, loaded_crate) in self.crates.get() {
+			for (task_name, fulfiller) in &loaded_crate.tasks {
+				let task_children = children
+					.remove(&FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					})
+					.unwrap_or_default();
+				fulfiller.children.set(task_children).unwrap();
 			}
 		}
 		info!("Connected fulfiller shared references.");
@@ -272,7 +446,7 @@ impl LoadedComposition {
 	fn create_fulfiller_chains(
 		&mut self,
 		composition: &UnloadedComposition,
-	) -> Result<(), Box<dyn Error>> {
+	) -> Result<(), CustardError> {
 		info!("Generating optimal fulfiller chains.");
 		//TODO: tests
 		let mut chains = vec![];
@@ -315,13 +489,36 @@ impl LoadedComposition {
 					break;
 				}
 
-				last_node = last_node_contents.parents[0].clone();
+				let parent = &last_node_contents.parents[0];
+				let parent_contents = composition
+					.crates
+					.get(&parent.crate_name)
+					.unwrap()
+					.tasks
+					.get(&parent.task_name)
+					.unwrap();
+				if parent_contents.main_thread != last_node_contents.main_thread
+				{
+					break;
+				}
+
+				last_node = parent.clone();
 			}
 
 			chain.reverse();
+			let first_name = chain_names.last().unwrap().clone();
+			let main_thread = composition
+				.crates
+				.get(&first_name.crate_name)
+				.unwrap()
+				.tasks
+				.get(&first_name.task_name)
+				.unwrap()
+				.main_thread;
 			let fulfiller_chain = FulfillerChain {
-				first_name: chain_names.last().unwrap().clone(),
+				first_name,
 				chain,
+				main_thread,
 			};
 			chains.push(Arc::new(fulfiller_chain));
 		}
@@ -332,99 +529,680 @@ impl LoadedComposition {
 		Ok(())
 	}
 
-	fn cross_access_check(
+	/// Check that every parent, every accessed datachunk and every accessed partition is in the composition. The other checks assume this.
+	fn reference_check(
 		composition: &UnloadedComposition,
-	) -> Result<(), Box<dyn Error>> {
-		info!("Checking unloaded composition for datachunk access violations.");
+	) -> Result<(), CustardError> {
+		info!("Checking unloaded composition for dangling references.");
 		for (crate_name, crate_contents) in &composition.crates {
 			for (task_name, task_contents) in &crate_contents.tasks {
-				for (other_crate_name, other_crate_contents) in
-					&composition.crates
-				{
-					if crate_name == other_crate_name {
+				let full_name = FullTaskName {
+					crate_name: crate_name.clone(),
+					task_name: task_name.clone(),
+				};
+				for parent in &task_contents.parents {
+					if composition.get_unloaded_task(parent).is_some() {
 						continue;
 					}
-					for (other_task_name, other_task_contents) in
-						&other_crate_contents.tasks
+					let mut candidates = vec![];
+					for (other_crate_name, other_crate_contents) in
+						&composition.crates
 					{
-						if task_name == other_task_name {
-							continue;
+						for other_task_name in other_crate_contents.tasks.keys()
+						{
+							candidates.push((
+								format!(
+									"{}/{}",
+									other_crate_name.get(),
+									other_task_name.get()
+								),
+								FullTaskName {
+									crate_name: other_crate_name.clone(),
+									task_name: other_task_name.clone(),
+								},
+							));
 						}
-						if !composition.are_tasks_unsynchronized(
-							FullTaskName {
+					}
+					return Err(CustardParentNotFoundError {
+						offending_task: parent.clone(),
+						required_by: full_name,
+						suggestion: Self::closest_name(
+							format!(
+								"{}/{}",
+								parent.crate_name.get(),
+								parent.task_name.get()
+							),
+							candidates,
+						),
+					}
+					.into());
+				}
+				Self::task_reference_check(
+					composition,
+					&full_name,
+					task_contents,
+				)?;
+			}
+		}
+		info!("No dangling references were found.");
+		Ok(())
+	}
+
+	fn task_reference_check(
+		composition: &UnloadedComposition,
+		task_name: &FullTaskName,
+		task_contents: &UnloadedTask,
+	) -> Result<(), CustardError> {
+		for access in &task_contents.accesses {
+			let datachunk = composition
+				.crates
+				.get(&access.of.crate_name)
+				.and_then(|datachunk_crate| {
+					datachunk_crate.datachunks.get(&access.of.datachunk_name)
+				});
+			if let Some(datachunk) = datachunk {
+				let partition = match &access.partition {
+					Some(v) => v,
+					None => continue,
+				};
+				if datachunk.partitions.contains(partition) {
+					continue;
+				}
+				return Err(CustardPartitionNotFoundError {
+					datachunk: access.of.clone(),
+					offending_partition: partition.clone(),
+					accessed_by: task_name.clone(),
+					suggestion: closest_match(
+						partition.as_str(),
+						datachunk.partitions.iter().map(|v| v.as_str()),
+					)
+					.map(|v| v.to_owned()),
+				}
+				.into());
+			}
+			let mut candidates = vec![];
+			for (crate_name, crate_contents) in &composition.crates {
+				for datachunk_name in crate_contents.datachunks.keys() {
+					candidates.push((
+						format!(
+							"{}/{}",
+							crate_name.get(),
+							datachunk_name.get()
+						),
+						FullDatachunkName {
+							crate_name: crate_name.clone(),
+							datachunk_name: datachunk_name.clone(),
+						},
+					));
+				}
+			}
+			return Err(CustardDatachunkNotFoundError {
+				offending_datachunk: access.of.clone(),
+				accessed_by: task_name.clone(),
+				suggestion: Self::closest_name(
+					format!(
+						"{}/{}",
+						access.of.crate_name.get(),
+						access.of.datachunk_name.get()
+					),
+					candidates,
+				),
+			}
+			.into());
+		}
+		Ok(())
+	}
+
+	/// The name in `candidates`, given as `crate/name` alongside the name itself, that is closest to `target`.
+	fn closest_name<T: Clone>(
+		target: String,
+		candidates: Vec<(String, T)>,
+	) -> Option<T> {
+		let closest = closest_match(
+			target.as_str(),
+			candidates.iter().map(|(v, _)| v.as_str()),
+		)?;
+		candidates
+			.iter()
+			.find(|(v, _)| v == closest)
+			.map(|(_, name)| name.clone())
+	}
+
+	fn access_type_check(
+		composition: &UnloadedComposition,
+	) -> Result<(), CustardError> {
+		info!("Checking unloaded composition for datachunk type mismatches.");
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				for access in &task_contents.accesses {
+					let expected_type = match &access.type_name {
+						Some(v) => v,
+						None => continue,
+					};
+					let datachunk = match composition
+						.crates
+						.get(&access.of.crate_name)
+						.and_then(|datachunk_crate| {
+							datachunk_crate
+								.datachunks
+								.get(&access.of.datachunk_name)
+						}) {
+						Some(v) => v,
+						None => continue,
+					};
+					if !LoadedDatachunk::type_name_matches(
+						&datachunk.type_name,
+						expected_type,
+					) {
+						return Err(CustardDatachunkTypeMismatchError {
+							datachunk: access.of.clone(),
+							expected_by: Some(FullTaskName {
 								crate_name: crate_name.clone(),
 								task_name: task_name.clone(),
-							},
-							FullTaskName {
-								crate_name: other_crate_name.clone(),
-								task_name: other_task_name.clone(),
-							},
-						) {
-							continue;
-						}
-						for access in &task_contents.accesses {
-							for other_access in &other_task_contents.accesses {
-								if access.of == other_access.of {
-									if !access
-										.mut_immut
-										.commensurable(&other_access.mut_immut)
-									{
-										return Err(Box::new(
-											CustardDatachunkAccessError {
-												task_a: FullTaskName {
-													crate_name: crate_name
-														.clone(),
-													task_name: task_name
-														.clone(),
-												},
-												task_b: FullTaskName {
-													crate_name:
-														other_crate_name.clone(),
-													task_name: other_task_name
-														.clone(),
-												},
-												datachunk: access.of.clone(),
-											},
-										));
-									}
-								}
-							}
+							}),
+							expected_type: expected_type.clone(),
+							found_type: datachunk.type_name.clone(),
 						}
+						.into());
 					}
 				}
 			}
 		}
+		info!("No datachunk type mismatches were found.");
+		Ok(())
+	}
+
+	fn cross_access_check(
+		composition: &UnloadedComposition,
+	) -> Result<(), CustardError> {
+		info!("Checking unloaded composition for datachunk access violations.");
+		let mut tasks = vec![];
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				tasks.push((
+					FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					},
+					task_contents,
+				));
+			}
+		}
+		let happens_before = HappensBefore::new(composition);
+		let indices: Vec<usize> = tasks
+			.iter()
+			.map(|(task_name, _)| happens_before.index_of(task_name).unwrap())
+			.collect();
+		//every unordered pair once, including tasks of the same crate and tasks with the same name in different crates
+		for (i, (task_name, task_contents)) in tasks.iter().enumerate() {
+			for (j, (other_task_name, other_task_contents)) in
+				tasks.iter().enumerate().skip(i + 1)
+			{
+				if !happens_before.are_unsynchronized_at(indices[i], indices[j])
+				{
+					continue;
+				}
+				Self::commensurable_access_check(
+					task_name.clone(),
+					task_contents,
+					other_task_name.clone(),
+					other_task_contents,
+				)?;
+			}
+		}
 		info!("No datachunk access violations were found :P.");
 		Ok(())
 	}
 
+	fn pair_access_check(
+		happens_before: &HappensBefore,
+		task_name: FullTaskName,
+		task_contents: &UnloadedTask,
+		other_task_name: FullTaskName,
+		other_task_contents: &UnloadedTask,
+	) -> Result<(), CustardError> {
+		if !happens_before
+			.are_tasks_unsynchronized(&task_name, &other_task_name)
+		{
+			return Ok(());
+		}
+		Self::commensurable_access_check(
+			task_name,
+			task_contents,
+			other_task_name,
+			other_task_contents,
+		)
+	}
+
+	/// Check the accesses of two tasks that may run at the same time.
+	fn commensurable_access_check(
+		task_name: FullTaskName,
+		task_contents: &UnloadedTask,
+		other_task_name: FullTaskName,
+		other_task_contents: &UnloadedTask,
+	) -> Result<(), CustardError> {
+		for access in &task_contents.accesses {
+			for other_access in &other_task_contents.accesses {
+				if access.conflicts_with(other_access) {
+					return Err(CustardDatachunkAccessError {
+						task_a: task_name,
+						task_b: other_task_name,
+						datachunk: access.of.clone(),
+					}
+					.into());
+				}
+			}
+		}
+		Ok(())
+	}
+
 	pub fn check(
 		unchecked: &UnloadedComposition,
-	) -> Result<Checked, Arc<dyn Error>> {
+	) -> Result<Checked, CustardError> {
 		info!("Commencing check of unloaded composition.");
+		Self::reference_check(unchecked)?;
+		Self::access_type_check(unchecked)?;
 		Self::cross_access_check(unchecked)?;
 		Self::ancestor_check(unchecked)?;
 		info!("Unloaded composition is valid, check completed.");
 		Ok(Checked { a: () })
 	}
 
-	pub fn run(&self) -> InstanceControlFlow {
-		info!("Generating thread pool.");
-		let pool = ThreadPool::new(8); //TODO: make thread count and maybe other attributes configurable
-		info!("Generated thread pool.");
+	/// Find what [check](Self::check) allows but is probably a mistake, leaving out what the composition's `allow_lints` lists. Meant to be run on a checked composition.
+	pub fn lint(composition: &UnloadedComposition) -> Vec<CompositionLint> {
+		let mut lints = vec![];
+		Self::datachunk_lints(composition, &mut lints);
+		Self::redundant_parent_lints(composition, &mut lints);
+		Self::task_without_accesses_lints(composition, &mut lints);
+		Self::reachable_entrypoint_lints(composition, &mut lints);
+		lints.retain(|lint| {
+			!composition
+				.allow_lints
+				.get(&lint.kind)
+				.is_some_and(|allow| allow.allows(&lint.subject))
+		});
+		lints
+	}
+
+	fn datachunk_lints(
+		composition: &UnloadedComposition,
+		lints: &mut Vec<CompositionLint>,
+	) {
+		let mut accessed_by = BTreeMap::<
+			FullDatachunkName,
+			Vec<(FullTaskName, AccessType)>,
+		>::new();
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				for access in &task_contents.accesses {
+					accessed_by.entry(access.of.clone()).or_default().push((
+						FullTaskName {
+							crate_name: crate_name.clone(),
+							task_name: task_name.clone(),
+						},
+						access.mut_immut,
+					));
+				}
+			}
+		}
 
+		for (crate_name, crate_contents) in &composition.crates {
+			for datachunk_name in crate_contents.datachunks.keys() {
+				let full_name = FullDatachunkName {
+					crate_name: crate_name.clone(),
+					datachunk_name: datachunk_name.clone(),
+				};
+				let accesses = match accessed_by.get(&full_name) {
+					Some(v) => v,
+					None => {
+						lints.push(CompositionLint {
+							kind: LintKind::UnusedDatachunk,
+							subject: LintSubject::Datachunk(full_name),
+							detail: "no task accesses it".to_owned(),
+						});
+						continue;
+					}
+				};
+				let only_task = &accesses[0].0;
+				if accesses.iter().all(|(task, _)| task == only_task)
+					&& accesses.iter().any(|(_, mut_immut)| {
+						mut_immut == &AccessType::MutableAccess
+					}) {
+					lints.push(CompositionLint {
+						kind: LintKind::UnreadMutation,
+						subject: LintSubject::Datachunk(full_name),
+						detail: format!(
+							"only {} accesses it, and it does so mutably",
+							only_task
+						),
+					});
+				}
+			}
+		}
+	}
+
+	fn redundant_parent_lints(
+		composition: &UnloadedComposition,
+		lints: &mut Vec<CompositionLint>,
+	) {
+		let happens_before = HappensBefore::new(composition);
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				let full_name = FullTaskName {
+					crate_name: crate_name.clone(),
+					task_name: task_name.clone(),
+				};
+				let parents = &task_contents.parents;
+				for (i, parent) in parents.iter().enumerate() {
+					if parents[..i].contains(parent) {
+						lints.push(CompositionLint {
+							kind: LintKind::RedundantParent,
+							subject: LintSubject::Task(full_name.clone()),
+							detail: format!("{} is listed twice", parent),
+						});
+						continue;
+					}
+					let implied_by = parents.iter().find(|other| {
+						*other != parent
+							&& happens_before.happens_before(parent, other)
+					});
+					if let Some(implied_by) = implied_by {
+						lints.push(CompositionLint {
+							kind: LintKind::RedundantParent,
+							subject: LintSubject::Task(full_name.clone()),
+							detail: format!(
+								"{} already runs after {}",
+								implied_by, parent
+							),
+						});
+					}
+				}
+			}
+		}
+	}
+
+	fn task_without_accesses_lints(
+		composition: &UnloadedComposition,
+		lints: &mut Vec<CompositionLint>,
+	) {
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				if task_contents.accesses.is_empty() {
+					lints.push(CompositionLint {
+						kind: LintKind::TaskWithoutAccesses,
+						subject: LintSubject::Task(FullTaskName {
+							crate_name: crate_name.clone(),
+							task_name: task_name.clone(),
+						}),
+						detail: "it accesses no datachunks".to_owned(),
+					});
+				}
+			}
+		}
+	}
+
+	/// An entrypoint is reachable if, were it not an entrypoint, every one of its parents would still run: each is another entrypoint or has only parents that would run.
+	fn reachable_entrypoint_lints(
+		composition: &UnloadedComposition,
+		lints: &mut Vec<CompositionLint>,
+	) {
+		let mut tasks = vec![];
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				tasks.push((
+					FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					},
+					task_contents,
+				));
+			}
+		}
+
+		for (entrypoint, _) in tasks.iter().filter(|(_, v)| v.entrypoint) {
+			let mut runs: BTreeSet<&FullTaskName> = tasks
+				.iter()
+				.filter(|(name, v)| v.entrypoint && name != entrypoint)
+				.map(|(name, _)| name)
+				.collect();
+			loop {
+				let before = runs.len();
+				for (name, contents) in &tasks {
+					if !runs.contains(name)
+						&& !contents.parents.is_empty()
+						&& contents.parents.iter().all(|v| runs.contains(v))
+					{
+						runs.insert(name);
+					}
+				}
+				if runs.len() == before {
+					break;
+				}
+			}
+			if runs.contains(entrypoint) {
+				lints.push(CompositionLint {
+					kind: LintKind::ReachableEntrypoint,
+					subject: LintSubject::Task(entrypoint.clone()),
+					detail: "other entrypoints already reach it every cycle"
+						.to_owned(),
+				});
+			}
+		}
+	}
+
+	/// Apply `edits` to `current` (the composition this was loaded from) and check the result. Only the added tasks are checked for cycles and access violations, since removals cannot leave a remaining task's ancestors changed (see [UnloadedComposition::with_task_edits]).
+	pub fn check_task_edits(
+		&self,
+		current: &UnloadedComposition,
+		edits: &TaskEdits,
+	) -> Result<(UnloadedComposition, Checked), CustardError> {
+		info!("Commencing check of task edits.");
+		let edited = current.with_task_edits(edits)?;
+		Self::access_type_check(&edited)?;
+		let happens_before = HappensBefore::new(&edited);
+
+		for (task_name, unloaded_task) in &edits.add {
+			let type_loaded =
+				match current.crates.get(&task_name.crate_name).and_then(|v| {
+					self.user_libraries
+						.get(v.library_name(&task_name.crate_name))
+				}) {
+					Some(user_library) => user_library
+						.has_task_type(unloaded_task.type_name.as_str()),
+					None => false,
+				};
+			if !type_loaded {
+				return Err(CustardTaskTypeNotLoadedError {
+					offending_task: task_name.clone(),
+					type_name: unloaded_task.type_name.clone(),
+				}
+				.into());
+			}
+			Self::task_reference_check(&edited, task_name, unloaded_task)?;
+
+			Self::task_ancestor_check(&edited, task_name.clone())?;
+
+			for (other_crate_name, other_crate_contents) in &edited.crates {
+				for (other_task_name, other_task_contents) in
+					&other_crate_contents.tasks
+				{
+					let other_full_name = FullTaskName {
+						crate_name: other_crate_name.clone(),
+						task_name: other_task_name.clone(),
+					};
+					if &other_full_name == task_name {
+						continue;
+					}
+					Self::pair_access_check(
+						&happens_before,
+						task_name.clone(),
+						unloaded_task,
+						other_full_name,
+						other_task_contents,
+					)?;
+				}
+			}
+		}
+		info!("Task edits are valid, check completed.");
+		Ok((edited, Checked { a: () }))
+	}
+
+	/// Check that `hooks` fit `composition`: each hook's accesses are checked as accesses of its boundary task against every task that is not disabled, and a task may only be disabled by a hook that mutably accesses every datachunk the task writes. Returns the tasks the hooks disable.
+	pub fn check_cycle_hooks(
+		composition: &UnloadedComposition,
+		hooks: &[Arc<dyn CycleHook>],
+	) -> Result<BTreeSet<FullTaskName>, CustardError> {
+		info!("Checking cycle hooks.");
+		let mut disabled = BTreeSet::new();
+		for hook in hooks {
+			let provided: BTreeSet<_> = hook
+				.accesses()
+				.into_iter()
+				.filter(|access| access.mut_immut == AccessType::MutableAccess)
+				.map(|access| access.of)
+				.collect();
+			for task_name in hook.disabled_tasks(composition) {
+				let task = match composition.get_unloaded_task(&task_name) {
+					Some(v) => v,
+					None => {
+						return Err(CustardTaskNotFoundError {
+							offending_task: task_name,
+						}
+						.into())
+					}
+				};
+				for access in &task.accesses {
+					if access.mut_immut == AccessType::MutableAccess
+						&& !provided.contains(&access.of)
+					{
+						return Err(CustardDisabledTaskWritesError {
+							disabled_task: task_name.clone(),
+							boundary: hook.boundary().clone(),
+							unprovided: access.of.clone(),
+						}
+						.into());
+					}
+				}
+				disabled.insert(task_name);
+			}
+		}
+		let happens_before = HappensBefore::new(composition);
+		for hook in hooks {
+			let boundary = hook.boundary();
+			let mut boundary_task = match composition
+				.crates
+				.get(&boundary.crate_name)
+				.and_then(|v| v.tasks.get(&boundary.task_name))
+			{
+				Some(v) => v.clone(),
+				None => {
+					return Err(CustardTaskNotFoundError {
+						offending_task: boundary.clone(),
+					}
+					.into())
+				}
+			};
+			boundary_task.accesses.append(&mut hook.accesses());
+			Self::task_reference_check(composition, boundary, &boundary_task)?;
+			for (other_crate_name, other_crate_contents) in &composition.crates
+			{
+				for (other_task_name, other_task_contents) in
+					&other_crate_contents.tasks
+				{
+					let other_full_name = FullTaskName {
+						crate_name: other_crate_name.clone(),
+						task_name: other_task_name.clone(),
+					};
+					if &other_full_name == boundary
+						|| disabled.contains(&other_full_name)
+					{
+						continue;
+					}
+					Self::pair_access_check(
+						&happens_before,
+						boundary.clone(),
+						&boundary_task,
+						other_full_name,
+						other_task_contents,
+					)?;
+				}
+			}
+		}
+		Ok(disabled)
+	}
+
+	/// Attach `hooks` to the fulfillers of their boundary tasks, replacing any hooks attached before. The hooks are checked with [check_cycle_hooks](Self::check_cycle_hooks) first, and nothing changes if they do not fit.
+	pub fn attach_cycle_hooks(
+		&mut self,
+		composition: &UnloadedComposition,
+		hooks: &[Arc<dyn CycleHook>],
+	) -> Result<(), CustardError> {
+		let disabled = Self::check_cycle_hooks(composition, hooks)?;
+
+		for (crate_name, crate_contents) in self.crates.get() {
+			for (task_name, fulfiller) in &crate_contents.tasks {
+				let full_name = FullTaskName {
+					crate_name: crate_name.clone(),
+					task_name: task_name.clone(),
+				};
+				let mut_fulfiller =
+					unsafe { &mut *(Arc::as_ptr(fulfiller) as *mut Fulfiller) };
+				mut_fulfiller.cycle_hooks = hooks
+					.iter()
+					.filter(|hook| hook.boundary() == &full_name)
+					.map(|hook| {
+						BoundCycleHook::new(hook.clone(), self.crates.clone())
+					})
+					.collect();
+				mut_fulfiller.disabled = disabled.contains(&full_name);
+				if mut_fulfiller.disabled {
+					info!("Task disabled by a cycle hook: {:?}", full_name);
+				}
+			}
+		}
+		info!("Attached cycle hooks.");
+		Ok(())
+	}
+
+	/// The most cycles any entrypoint has run since this composition was loaded.
+	pub fn cycles(&self) -> u64 {
+		let mut ret = 0;
+		for crate_contents in self.crates.get().values() {
+			for fulfiller in crate_contents.tasks.values() {
+				ret = ret.max(fulfiller.done.cycles());
+			}
+		}
+		ret
+	}
+
+	/// Run the composition until every fulfiller has ceased, or until an entrypoint reaches a limit in `run_options`. Async tasks are driven by `executor`, and those still pending once every fulfiller has ceased are dropped.
+	pub fn run(
+		&self,
+		executor: &Arc<Executor>,
+		run_options: RunOptions,
+	) -> InstanceControlFlow {
+		info!("Generating scheduler.");
+		let scheduler: Arc<dyn Scheduler> =
+			Arc::new(WorkStealingScheduler::new(8)); //TODO: make thread count and maybe other attributes configurable
+		info!("Generated scheduler.");
+
+		let context = RunContext {
+			quit: self.task_completion.clone(),
+			scheduler,
+			executor: Arc::downgrade(executor),
+			all_chains: self.fulfiller_chains.clone(),
+			instance_control_flow: self.control_flow.clone(),
+			run_options,
+		};
 		for chain in &*self.fulfiller_chains {
-			chain.clone().attempt_to_run(
-				self.task_completion.clone(),
-				pool.clone(),
-				self.fulfiller_chains.clone(),
-				self.control_flow.clone(),
-			);
+			chain.clone().attempt_to_run(&context);
 		}
 
 		info!("Waiting for tasks to complete.");
 
 		self.task_completion.main_thread_wait();
+		//futures still pending belong to this run, and must not complete into the next
+		executor.cancel_pending();
 		info!("Tasks completed.");
 
 		info!("Locking control flow.");
@@ -434,3 +1212,311 @@ impl LoadedComposition {
 		control_flow
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::LoadedComposition;
+
+	use crate::{
+		composition::{
+			loaded::loaded_crate::LoadedCrate,
+			unloaded::{
+				composition_builder::{
+					CompositionBuilder, CrateBuilder, TaskBuilder,
+				},
+				unloaded_composition::UnloadedComposition,
+				unloaded_datachunk::UnloadedDatachunk,
+			},
+		},
+		concurrency::access::{Access, AccessType},
+		errors::custard_error::CustardError,
+		identify::{
+			crate_name::CrateName, custard_name::CustardName,
+			datachunk_name::FullDatachunkName, task_name::FullTaskName,
+		},
+		recording::cycle_hook::CycleHook,
+	};
+
+	use std::{
+		collections::{BTreeMap, BTreeSet},
+		sync::Arc,
+	};
+
+	/// Writes `arm/pose` at the start of `arm/start` in place of `arm/a`, as a replay would.
+	#[derive(Debug)]
+	struct PoseHook(FullTaskName);
+
+	impl CycleHook for PoseHook {
+		fn boundary(&self) -> &FullTaskName {
+			&self.0
+		}
+
+		fn accesses(&self) -> Vec<Access> {
+			vec![Access {
+				of: FullDatachunkName::new("arm".to_owned(), "pose".to_owned()),
+				mut_immut: AccessType::MutableAccess,
+				type_name: None,
+				partition: None,
+			}]
+		}
+
+		fn disabled_tasks(
+			&self,
+			_composition: &UnloadedComposition,
+		) -> BTreeSet<FullTaskName> {
+			BTreeSet::from([FullTaskName::new(
+				"arm".to_owned(),
+				"a".to_owned(),
+			)])
+		}
+
+		fn on_cycle(&self, _crates: &BTreeMap<CrateName, LoadedCrate>) {}
+	}
+
+	fn composition_with(task_builder: TaskBuilder) -> CompositionBuilder {
+		CompositionBuilder::new().with_crate(
+			"arm",
+			CrateBuilder::new()
+				.datachunk("pose", UnloadedDatachunk::new("Pose", "pose.ron"))
+				.task("tick", task_builder),
+		)
+	}
+
+	/// `start` begins each cycle and waits for `a` and `b`, which both mutate `arm/pose`.
+	fn fork(a: (&str, &str), b: (&str, &str)) -> CompositionBuilder {
+		let branch = TaskBuilder::new("T", "t.ron")
+			.parent("arm", "start")
+			.access("arm", "pose", AccessType::MutableAccess);
+		CompositionBuilder::new()
+			.with_crate(
+				"arm",
+				CrateBuilder::new()
+					.datachunk(
+						"pose",
+						UnloadedDatachunk::new("Pose", "pose.ron"),
+					)
+					.task(
+						"start",
+						TaskBuilder::new("T", "t.ron")
+							.entrypoint(true)
+							.parent(a.0, a.1)
+							.parent(b.0, b.1),
+					),
+			)
+			.with_task(
+				FullTaskName::new(a.0.to_owned(), a.1.to_owned()),
+				branch.clone(),
+			)
+			.with_task(
+				FullTaskName::new(b.0.to_owned(), b.1.to_owned()),
+				branch,
+			)
+	}
+
+	#[test]
+	fn hooks_only_disable_tasks_whose_writes_they_provide() {
+		let hooks: [Arc<dyn CycleHook>; 1] = [Arc::new(PoseHook(
+			FullTaskName::new("arm".to_owned(), "start".to_owned()),
+		))];
+		let composition = fork(("arm", "a"), ("arm", "b")).build();
+		assert!(LoadedComposition::check_cycle_hooks(&composition, &hooks)
+			.unwrap()
+			.contains(&FullTaskName::new("arm".to_owned(), "a".to_owned())));
+
+		let composition = fork(("arm", "a"), ("arm", "b"))
+			.with_task(
+				FullTaskName::new("arm".to_owned(), "a".to_owned()),
+				TaskBuilder::new("T", "t.ron")
+					.parent("arm", "start")
+					.access("arm", "pose", AccessType::MutableAccess)
+					.access("arm", "log", AccessType::MutableAccess),
+			)
+			.build();
+		match LoadedComposition::check_cycle_hooks(&composition, &hooks) {
+			Err(CustardError::DisabledTaskWrites(e)) => {
+				assert_eq!(e.unprovided.datachunk_name.get(), "log")
+			}
+			_ => panic!("expected the write to arm/log to be refused"),
+		}
+	}
+
+	#[test]
+	fn typed_accesses_match_type_paths() {
+		let typed = |type_name: &str| {
+			composition_with(
+				TaskBuilder::new("T", "t.ron")
+					.entrypoint(true)
+					.parent("arm", "tick")
+					.typed_access(
+						"arm",
+						"pose",
+						AccessType::ImmutableAccess,
+						type_name,
+					),
+			)
+			.build()
+		};
+		assert!(LoadedComposition::check(&typed("Pose")).is_ok());
+		assert!(LoadedComposition::check(&typed("arm::Pose")).is_ok());
+		assert!(matches!(
+			LoadedComposition::check(&typed("Posture")),
+			Err(CustardError::DatachunkTypeMismatch(_))
+		));
+	}
+
+	#[test]
+	fn conflicts_within_a_crate() {
+		let composition = fork(("arm", "a"), ("arm", "b")).build();
+		match LoadedComposition::check(&composition) {
+			Err(CustardError::DatachunkAccess(e)) => {
+				assert_eq!(e.task_a.crate_name, e.task_b.crate_name)
+			}
+			_ => panic!("expected an access conflict"),
+		}
+
+		//once b runs after a, they no longer conflict
+		let composition = fork(("arm", "a"), ("arm", "b"))
+			.with_task(
+				FullTaskName::new("arm".to_owned(), "b".to_owned()),
+				TaskBuilder::new("T", "t.ron").parent("arm", "a").access(
+					"arm",
+					"pose",
+					AccessType::MutableAccess,
+				),
+			)
+			.with_task(
+				FullTaskName::new("arm".to_owned(), "start".to_owned()),
+				TaskBuilder::new("T", "t.ron")
+					.entrypoint(true)
+					.parent("arm", "b"),
+			)
+			.build();
+		assert!(LoadedComposition::check(&composition).is_ok());
+	}
+
+	#[test]
+	fn conflicts_between_same_named_tasks() {
+		let composition = fork(("arm", "tick"), ("leg", "tick")).build();
+		match LoadedComposition::check(&composition) {
+			Err(CustardError::DatachunkAccess(e)) => {
+				assert_eq!(e.task_a.task_name, e.task_b.task_name)
+			}
+			_ => panic!("expected an access conflict"),
+		}
+	}
+
+	#[test]
+	fn disjoint_partitions_do_not_conflict() {
+		let partitioned = |a: &str, b: &str| {
+			let branch = |partition: &str| {
+				TaskBuilder::new("T", "t.ron")
+					.parent("arm", "start")
+					.partition_access(
+						"arm",
+						"pose",
+						partition,
+						AccessType::MutableAccess,
+					)
+			};
+			fork(("arm", "a"), ("arm", "b"))
+				.with_datachunk(
+					FullDatachunkName::new("arm".to_owned(), "pose".to_owned()),
+					UnloadedDatachunk::new("Pose", "pose.ron")
+						.partition("left")
+						.partition("right"),
+				)
+				.with_task(
+					FullTaskName::new("arm".to_owned(), "a".to_owned()),
+					branch(a),
+				)
+				.with_task(
+					FullTaskName::new("arm".to_owned(), "b".to_owned()),
+					branch(b),
+				)
+				.build()
+		};
+		assert!(LoadedComposition::check(&partitioned("left", "right")).is_ok());
+		assert!(matches!(
+			LoadedComposition::check(&partitioned("left", "left")),
+			Err(CustardError::DatachunkAccess(_))
+		));
+		match LoadedComposition::check(&partitioned("left", "rigt")) {
+			Err(CustardError::PartitionNotFound(e)) => {
+				assert_eq!(e.suggestion.as_deref(), Some("right"))
+			}
+			_ => panic!("expected a missing partition"),
+		}
+
+		//a partition still conflicts with the whole datachunk
+		let composition = fork(("arm", "a"), ("arm", "b"))
+			.with_datachunk(
+				FullDatachunkName::new("arm".to_owned(), "pose".to_owned()),
+				UnloadedDatachunk::new("Pose", "pose.ron").partition("left"),
+			)
+			.with_task(
+				FullTaskName::new("arm".to_owned(), "a".to_owned()),
+				TaskBuilder::new("T", "t.ron")
+					.parent("arm", "start")
+					.partition_access(
+						"arm",
+						"pose",
+						"left",
+						AccessType::MutableAccess,
+					),
+			)
+			.build();
+		assert!(matches!(
+			LoadedComposition::check(&composition),
+			Err(CustardError::DatachunkAccess(_))
+		));
+	}
+
+	#[test]
+	fn dangling_references_suggest_near_misses() {
+		let composition = composition_with(
+			TaskBuilder::new("Tick", "tick.ron")
+				.entrypoint(true)
+				.parent("arm", "tick")
+				.access("arm", "poze", AccessType::MutableAccess),
+		)
+		.build();
+		match LoadedComposition::check(&composition) {
+			Err(CustardError::DatachunkNotFound(e)) => assert_eq!(
+				e.suggestion,
+				Some(FullDatachunkName::new(
+					"arm".to_owned(),
+					"pose".to_owned()
+				))
+			),
+			_ => panic!("expected a missing datachunk"),
+		}
+
+		let composition = composition_with(
+			TaskBuilder::new("Tick", "tick.ron")
+				.entrypoint(true)
+				.parent("armm", "tick"),
+		)
+		.build();
+		match LoadedComposition::check(&composition) {
+			Err(CustardError::ParentNotFound(e)) => assert_eq!(
+				e.suggestion,
+				Some(FullTaskName::new("arm".to_owned(), "tick".to_owned()))
+			),
+			_ => panic!("expected a missing parent"),
+		}
+
+		let composition = composition_with(
+			TaskBuilder::new("Tick", "tick.ron")
+				.entrypoint(true)
+				.parent("arm", "tick")
+				.access("legs", "stride", AccessType::ImmutableAccess),
+		)
+		.build();
+		match LoadedComposition::check(&composition) {
+			Err(CustardError::DatachunkNotFound(e)) => {
+				assert_eq!(e.suggestion, None)
+			}
+			_ => panic!("expected a missing datachunk"),
+		}
+	}
+}
@@ -3,25 +3,24 @@ use crate::{
 		loaded::{loaded_datachunk::LoadedDatachunk, loaded_task::LoadedTask},
 		unloaded::unloaded_crate::UnloadedCrate,
 	},
-	concurrency::{fulfiller::Fulfiller, ready::Ready},
-	dylib_management::safe_library::{
-		safe_library::{DebugMode, LibraryRecompile, SafeLibrary},
-		user_library::UserLibrary,
+	concurrency::{
+		fulfiller::Fulfiller, fulfiller_state::FulfillerState, ready::Ready,
+	},
+	dylib_management::safe_library::user_library::UserLibrary,
+	errors::{
+		custard_error::CustardError,
+		load_errors::custard_composition_requires_core_crate_error::CustardCompositionRequiresCoreCrateError,
 	},
-	errors::load_errors::custard_composition_requires_core_crate_error::CustardCompositionRequiresCoreCrateError,
 	identify::{
 		crate_name::CrateName,
-		datachunk_name::DatachunkName,
+		datachunk_name::{DatachunkName, FullDatachunkName},
 		task_name::{FullTaskName, TaskName},
 	},
 };
 
 use std::{
-	cell::RefCell,
 	collections::BTreeMap,
-	error::Error,
-	rc::Rc,
-	sync::{Arc, Mutex},
+	sync::{Arc, OnceLock},
 };
 
 #[derive(Debug)]
@@ -34,85 +33,92 @@ impl LoadedCrate {
 	pub fn new(
 		name: &CrateName,
 		unloaded_crate: &UnloadedCrate,
-		recompile: LibraryRecompile,
-		debug: DebugMode,
-		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
-		mut old_crate: Option<&mut (
+		user_library: &UserLibrary,
+		old_crate: Option<&mut (
 			BTreeMap<TaskName, LoadedTask>,
 			BTreeMap<DatachunkName, LoadedDatachunk>,
 		)>,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
 		let core_library = match &unloaded_crate.lib {
 			Some(v) => v,
 			None => {
-				return Err(Box::new(
-					CustardCompositionRequiresCoreCrateError {
-						offending_crate: name.clone(),
-					},
-				))
+				return Err(CustardCompositionRequiresCoreCrateError {
+					offending_crate: name.clone(),
+				}
+				.into())
 			}
 		};
-		let user_library =
-			UserLibrary::new(name.clone(), recompile, debug, drop_list)?;
+		//load everything that is not reused before taking anything from the old crate, so that an error leaves it whole
 		let mut datachunks = BTreeMap::new();
-
 		for (datachunk_name, unloaded_datachunk) in &unloaded_crate.datachunks {
-			datachunks.insert(datachunk_name.clone(), {
-				let mut datachunk = None;
-				if let Some(old_crate) = &mut old_crate {
-					if let Some(old_datachunk) =
-						old_crate.1.remove(datachunk_name)
-					{
-						datachunk = Some(old_datachunk);
-					}
+			if old_crate
+				.as_ref()
+				.is_some_and(|v| v.1.contains_key(datachunk_name))
+			{
+				continue;
+			}
+			datachunks.insert(
+				datachunk_name.clone(),
+				Some(LoadedDatachunk::new(
+					FullDatachunkName {
+						crate_name: name.clone(),
+						datachunk_name: datachunk_name.clone(),
+					},
+					unloaded_datachunk,
+					user_library,
+					&core_library,
+				)?),
+			);
+		}
+
+		let mut tasks = BTreeMap::new();
+		for (task_name, unloaded_task) in &unloaded_crate.tasks {
+			if old_crate
+				.as_ref()
+				.is_some_and(|v| v.0.contains_key(task_name))
+			{
+				continue;
+			}
+			tasks.insert(
+				task_name.clone(),
+				LoadedTask::new(
+					FullTaskName {
+						crate_name: name.clone(),
+						task_name: task_name.clone(),
+					},
+					unloaded_task,
+					user_library,
+					&core_library,
+				)?,
+			);
+		}
+
+		if let Some(old_crate) = old_crate {
+			for datachunk_name in unloaded_crate.datachunks.keys() {
+				if let Some(v) = old_crate.1.remove(datachunk_name) {
+					datachunks.insert(datachunk_name.clone(), Some(v));
 				}
-				match datachunk {
-					Some(v) => Some(v),
-					None => {
-						Some(LoadedDatachunk::new(
-							unloaded_datachunk,
-							&user_library,
-							&core_library,
-						)?)
-					}
+			}
+			for task_name in unloaded_crate.tasks.keys() {
+				if let Some(v) = old_crate.0.remove(task_name) {
+					tasks.insert(task_name.clone(), v);
 				}
-			});
+			}
 		}
 
 		let mut fulfillers = BTreeMap::new();
-
-		for (task_name, unloaded_task) in &unloaded_crate.tasks {
-			let full_name = FullTaskName {
-				crate_name: name.clone(),
-				task_name: task_name.clone(),
-			};
+		for (task_name, task) in tasks {
 			let fulfiller = Fulfiller {
-				cease: Mutex::new(false),
-				error: Mutex::new(false),
+				state: FulfillerState::new(),
+				children: OnceLock::new(),
 				children_chains: vec![],
-				done: Ready::new(unloaded_task.entrypoint),
+				done: Ready::new(unloaded_crate.tasks[&task_name].entrypoint),
 				prerequisites: vec![],
-				task: {
-					let mut task = None;
-					if let Some(old_crate) = &mut old_crate {
-						if let Some(old_task) = old_crate.0.remove(task_name) {
-							task = Some(old_task);
-						}
-					}
-					match task {
-						Some(v) => Some(v),
-						None => {
-							Some(LoadedTask::new(
-								full_name,
-								unloaded_task,
-								&user_library,
-								&core_library,
-							)?)
-						}
-					}
-				},
+				cycle_hooks: vec![],
+				disabled: false,
+				task: Some(task),
 			};
-			fulfillers.insert(task_name.clone(), Arc::new(fulfiller));
+			fulfillers.insert(task_name, Arc::new(fulfiller));
 		}
 
 		Ok(Self {
@@ -120,4 +126,55 @@ impl LoadedCrate {
 			tasks: fulfillers,
 		})
 	}
+
+	/// Take every loaded task and datachunk out of this crate so that they can be handed to a new `LoadedCrate` in a reload. The fulfillers left behind are empty.
+	pub(crate) fn take_contents(
+		&mut self,
+	) -> (
+		BTreeMap<TaskName, LoadedTask>,
+		BTreeMap<DatachunkName, LoadedDatachunk>,
+	) {
+		let mut old_tasks = BTreeMap::new();
+
+		for (task_name, old_fulfiller) in &self.tasks {
+			let task_name = task_name.clone();
+			let mut_fulfiller = unsafe {
+				&mut *(Arc::as_ptr(&old_fulfiller) as *mut Fulfiller)
+			};
+			let old_task = mut_fulfiller.task.take().unwrap();
+			old_tasks.insert(task_name, old_task);
+		}
+
+		let mut old_datachunks = BTreeMap::new();
+
+		for (datachunk_name, old_datachunk) in &mut self.datachunks {
+			let datachunk_name = datachunk_name.clone();
+			old_datachunks
+				.insert(datachunk_name, old_datachunk.take().unwrap());
+		}
+
+		(old_tasks, old_datachunks)
+	}
+
+	/// Undo [take_contents](Self::take_contents), taking back whichever of this crate's tasks and datachunks are in `contents`.
+	pub(crate) fn put_back_contents(
+		&mut self,
+		contents: &mut (
+			BTreeMap<TaskName, LoadedTask>,
+			BTreeMap<DatachunkName, LoadedDatachunk>,
+		),
+	) {
+		for (task_name, fulfiller) in &self.tasks {
+			if let Some(task) = contents.0.remove(task_name) {
+				let mut_fulfiller =
+					unsafe { &mut *(Arc::as_ptr(fulfiller) as *mut Fulfiller) };
+				mut_fulfiller.task = Some(task);
+			}
+		}
+		for (datachunk_name, datachunk) in &mut self.datachunks {
+			if let Some(v) = contents.1.remove(datachunk_name) {
+				*datachunk = Some(v);
+			}
+		}
+	}
 }
@@ -3,22 +3,33 @@ use crate::{
 	dylib_management::safe_library::{
 		core_library::CoreLibrary, user_library::UserLibrary,
 	},
+	errors::{
+		custard_error::CustardError,
+		datachunk_errors::{
+			custard_datachunk_not_partitionable_error::CustardDatachunkNotPartitionableError,
+			custard_datachunk_type_mismatch_error::CustardDatachunkTypeMismatchError,
+		},
+	},
+	identify::datachunk_name::FullDatachunkName,
 	user_types::datachunk::DatachunkObject,
 };
 
-use std::error::Error;
+use std::any::TypeId;
 
 #[derive(Debug)]
 pub struct LoadedDatachunk {
 	pub(crate) user_data: DatachunkObject,
+	/// The type of `user_data`, kept so that partitions can be downcast to without borrowing the whole datachunk.
+	pub(crate) type_id: TypeId,
 }
 
 impl LoadedDatachunk {
 	pub fn new(
+		name: FullDatachunkName,
 		unloaded_datachunk: &UnloadedDatachunk,
 		user_library: &UserLibrary,
 		core_library: &CoreLibrary,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
 		let deserialize_str = (core_library
 			.symbols
 			.as_ref()
@@ -26,14 +37,60 @@ impl LoadedDatachunk {
 			.unloaded_datachunk_contents)(Box::new(
 			unloaded_datachunk.deserialize_path.clone(),
 		))
-		.into_rust()?;
-
-		let ret = Ok(Self {
-			user_data: user_library.load_datachunk(
-				unloaded_datachunk.type_name.as_str(),
-				deserialize_str.as_str(),
-			)?,
-		});
+		.into_rust()
+		.map_err(|e| CustardError::CoreLibraryFunction {
+			crate_name: name.crate_name.clone(),
+			function: "__custard_unloaded_datachunk_contents__",
+			source: e,
+		})?;
+
+		let user_data = user_library.load_datachunk(
+			unloaded_datachunk.type_name.as_str(),
+			deserialize_str.as_str(),
+		)?;
+
+		Self::from_object(name, unloaded_datachunk, user_data)
+	}
+
+	/// Check a loaded datachunk against what the composition declares for it.
+	pub(crate) fn from_object(
+		name: FullDatachunkName,
+		unloaded_datachunk: &UnloadedDatachunk,
+		user_data: DatachunkObject,
+	) -> Result<Self, CustardError> {
+		let found_type = user_data.get().datachunk_type_name();
+		if !Self::type_name_matches(&unloaded_datachunk.type_name, found_type) {
+			return Err(CustardDatachunkTypeMismatchError {
+				datachunk: name,
+				expected_by: None,
+				expected_type: unloaded_datachunk.type_name.clone(),
+				found_type: found_type.to_owned(),
+			}
+			.into());
+		}
+
+		if !unloaded_datachunk.partitions.is_empty()
+			&& !user_data.get().is_partitionable()
+		{
+			return Err(CustardDatachunkNotPartitionableError {
+				datachunk: name,
+				type_name: found_type.to_owned(),
+			}
+			.into());
+		}
+
+		let type_id = mopa::Any::get_type_id(user_data.get());
+		let ret = Ok(Self { user_data, type_id });
 		ret
 	}
+
+	/// Whether two type names can name the same type. Compositions usually give bare names (`Particles`), while loaded types report their full path (`my_crate::Particles`), so a name also matches any path that ends in it. Used for every datachunk type comparison, so that accesses, compositions and loaded types agree.
+	pub(crate) fn type_name_matches(
+		type_name: &str,
+		other_type_name: &str,
+	) -> bool {
+		type_name == other_type_name
+			|| type_name.ends_with(&format!("::{}", other_type_name))
+			|| other_type_name.ends_with(&format!("::{}", type_name))
+	}
 }
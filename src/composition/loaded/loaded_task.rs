@@ -9,24 +9,28 @@ use crate::{
 	dylib_management::safe_library::{
 		core_library::CoreLibrary, user_library::UserLibrary,
 	},
-	errors::run_errors::custard_task_panic_error::CustardTaskPanicError,
+	errors::{
+		custard_error::CustardError,
+		run_errors::custard_task_panic_error::CustardTaskPanicError,
+	},
 	identify::{crate_name::CrateName, task_name::FullTaskName},
-	user_types::task::{TaskClosureType, TaskObject},
-	utils::mutable_arc::MutableArc,
+	user_types::task::{TaskClosure, TaskObject},
+	utils::{mutable_arc::MutableArc, panic_capture},
 };
 
 use std::{
 	collections::BTreeMap,
-	error::Error,
 	fmt::{self, Formatter},
-	panic::{self, AssertUnwindSafe},
+	panic::AssertUnwindSafe,
 	sync::Arc,
 };
 
 pub struct LoadedTask {
 	pub name: FullTaskName,
-	pub closure: Option<TaskClosureType>,
+	pub closure: Option<TaskClosure>,
 	pub user_data: TaskObject,
+	/// The dylib copy that `user_data` was loaded from.
+	pub library_path: String,
 	accesses: Vec<Access>,
 }
 
@@ -35,6 +39,7 @@ impl fmt::Debug for LoadedTask {
 		f.debug_struct("LoadedTask")
 			.field("accesses", &self.accesses)
 			.field("user_data", &self.user_data)
+			.field("library_path", &self.library_path)
 			.finish_non_exhaustive()
 	}
 }
@@ -45,7 +50,7 @@ impl LoadedTask {
 		unloaded_task: &UnloadedTask,
 		user_library: &UserLibrary,
 		core_library: &CoreLibrary,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
 		let fn_res = (core_library
 			.symbols
 			.as_ref()
@@ -54,7 +59,13 @@ impl LoadedTask {
 			unloaded_task.deserialize_path.clone(),
 		));
 
-		let deserialize_str = (fn_res).into_rust()?;
+		let deserialize_str = (fn_res).into_rust().map_err(|e| {
+			CustardError::CoreLibraryFunction {
+				crate_name: name.crate_name.clone(),
+				function: "__custard_unloaded_task_contents__",
+				source: e,
+			}
+		})?;
 
 		let accesses = unloaded_task.accesses.clone();
 
@@ -68,32 +79,55 @@ impl LoadedTask {
 			accesses,
 			closure: None,
 			user_data,
+			library_path: user_library.get_library_path().to_owned(),
 		});
 		ret
 	}
 
+	/// A task whose closure is given rather than built by a user library, for benches that dispatch fulfillers.
+	#[cfg(test)]
+	pub(crate) fn with_closure(
+		name: FullTaskName,
+		user_data: TaskObject,
+		closure: TaskClosure,
+	) -> Self {
+		Self {
+			name,
+			closure: Some(closure),
+			user_data,
+			library_path: String::new(),
+			accesses: vec![],
+		}
+	}
+
 	pub fn load_closure(
 		&mut self,
 		crate_table: MutableArc<BTreeMap<CrateName, LoadedCrate>>,
-	) -> Result<(), Box<dyn Error>> {
-		self.closure = {
-			let user_data = AssertUnwindSafe(self.user_data.clone());
-			let datachunk_getter = AssertUnwindSafe(Arc::new(
-				DatachunkGetter::new(crate_table, self.accesses.clone()),
-			));
-			match panic::catch_unwind(|| {
-				let mut task_impl = user_data.lock();
-				task_impl.run(self.name.clone(), datachunk_getter.clone())
-			}) {
-				Ok(v) => Some(v),
-				Err(e) => {
-					return Err(Box::new(CustardTaskPanicError {
-						offending_task: self.name.clone(),
-						error: e,
-					}))
-				}
-			}
-		};
+	) -> Result<(), CustardError> {
+		self.closure = Some(self.build_closure(crate_table)?);
 		Ok(())
 	}
+
+	/// Create the task's closure without storing it, so that a composition can build every closure before replacing any.
+	pub(crate) fn build_closure(
+		&self,
+		crate_table: MutableArc<BTreeMap<CrateName, LoadedCrate>>,
+	) -> Result<TaskClosure, CustardError> {
+		let user_data = AssertUnwindSafe(self.user_data.clone());
+		let datachunk_getter = AssertUnwindSafe(Arc::new(
+			DatachunkGetter::new(crate_table, self.accesses.clone()),
+		));
+		match panic_capture::catch_unwind(|| {
+			let mut task_impl = user_data.lock();
+			task_impl.run(self.name.clone(), datachunk_getter.clone())
+		}) {
+			Ok(v) => Ok(v),
+			Err(e) => Err(CustardTaskPanicError::new(
+				self.name.clone(),
+				self.library_path.clone(),
+				e,
+			)
+			.into()),
+		}
+	}
 }
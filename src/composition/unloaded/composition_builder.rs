@@ -0,0 +1,376 @@
+use crate::{
+	composition::unloaded::{
+		lint::{LintAllow, LintKind},
+		unloaded_composition::UnloadedComposition,
+		unloaded_crate::UnloadedCrate,
+		unloaded_datachunk::UnloadedDatachunk,
+		unloaded_task::UnloadedTask,
+	},
+	concurrency::access::{Access, AccessType},
+	identify::{
+		crate_name::CrateName,
+		custard_name::CustardName,
+		datachunk_name::{DatachunkName, FullDatachunkName},
+		task_name::{FullTaskName, TaskName},
+	},
+};
+
+use cargo_metadata::VersionReq;
+
+/// Puts together an [UnloadedComposition] without writing RON. Like a parsed root composition, the result has no core libraries until [load_children](UnloadedComposition::load_children) is called; crates added here are checked and loaded alongside the crates the children declare.
+///
+/// ```no_run
+/// # use custard_use::{
+/// #     composition::unloaded::{
+/// #         composition_builder::{CompositionBuilder, CrateBuilder, TaskBuilder},
+/// #         unloaded_datachunk::UnloadedDatachunk,
+/// #     },
+/// #     concurrency::access::AccessType,
+/// # };
+/// let composition = CompositionBuilder::new()
+///     .child("physics")
+///     .with_crate(
+///         "arm",
+///         CrateBuilder::new()
+///             .datachunk("pose", UnloadedDatachunk::new("Pose", "pose.ron"))
+///             .task(
+///                 "tick",
+///                 TaskBuilder::new("Tick", "tick.ron")
+///                     .entrypoint(true)
+///                     .parent("arm", "tick")
+///                     .access("arm", "pose", AccessType::MutableAccess),
+///             ),
+///     )
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CompositionBuilder {
+	composition: UnloadedComposition,
+}
+
+impl CompositionBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Start from an existing composition, to change it.
+	pub fn from_composition(composition: UnloadedComposition) -> Self {
+		Self { composition }
+	}
+
+	/// Load `name` and the crates its core library declares.
+	pub fn child(mut self, name: impl Into<String>) -> Self {
+		let name = CrateName::new(name.into());
+		if !self.composition.children.contains(&name) {
+			self.composition.children.push(name);
+		}
+		self
+	}
+
+	pub fn child_version(
+		mut self,
+		name: impl Into<String>,
+		required: VersionReq,
+	) -> Self {
+		self.composition
+			.child_versions
+			.insert(CrateName::new(name.into()), required);
+		self
+	}
+
+	/// Add to the places `kind` is allowed. See `allow_lints` in the RON format.
+	pub fn allow_lint(mut self, kind: LintKind, allow: LintAllow) -> Self {
+		self.composition
+			.allow_lints
+			.entry(kind)
+			.or_default()
+			.merge(allow);
+		self
+	}
+
+	/// Add a crate, replacing any crate of the same name.
+	pub fn with_crate(
+		mut self,
+		name: impl Into<String>,
+		crate_builder: CrateBuilder,
+	) -> Self {
+		self.composition
+			.crates
+			.insert(CrateName::new(name.into()), crate_builder.build());
+		self
+	}
+
+	pub fn without_crate(mut self, name: &CrateName) -> Self {
+		self.composition.crates.remove(name);
+		self
+	}
+
+	/// Add a task to a crate, replacing any task of the same name. The crate is created if it does not exist.
+	pub fn with_task(
+		mut self,
+		name: FullTaskName,
+		task_builder: TaskBuilder,
+	) -> Self {
+		self.composition
+			.crates
+			.entry(name.crate_name)
+			.or_default()
+			.tasks
+			.insert(name.task_name, task_builder.build());
+		self
+	}
+
+	pub fn without_task(mut self, name: &FullTaskName) -> Self {
+		if let Some(unloaded_crate) =
+			self.composition.crates.get_mut(&name.crate_name)
+		{
+			unloaded_crate.tasks.remove(&name.task_name);
+		}
+		self
+	}
+
+	/// Add a datachunk to a crate, replacing any datachunk of the same name. The crate is created if it does not exist.
+	pub fn with_datachunk(
+		mut self,
+		name: FullDatachunkName,
+		datachunk: UnloadedDatachunk,
+	) -> Self {
+		self.composition
+			.crates
+			.entry(name.crate_name)
+			.or_default()
+			.datachunks
+			.insert(name.datachunk_name, datachunk);
+		self
+	}
+
+	pub fn without_datachunk(mut self, name: &FullDatachunkName) -> Self {
+		if let Some(unloaded_crate) =
+			self.composition.crates.get_mut(&name.crate_name)
+		{
+			unloaded_crate.datachunks.remove(&name.datachunk_name);
+		}
+		self
+	}
+
+	pub fn build(self) -> UnloadedComposition {
+		self.composition
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CrateBuilder {
+	unloaded_crate: UnloadedCrate,
+}
+
+impl CrateBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Load this crate's tasks and datachunks from the library of `library`. See `instance_of` in the RON format.
+	pub fn instance_of(mut self, library: impl Into<String>) -> Self {
+		self.unloaded_crate.instance_of = Some(CrateName::new(library.into()));
+		self
+	}
+
+	pub fn datachunk(
+		mut self,
+		name: impl Into<String>,
+		datachunk: UnloadedDatachunk,
+	) -> Self {
+		self.unloaded_crate
+			.datachunks
+			.insert(DatachunkName::new(name.into()), datachunk);
+		self
+	}
+
+	pub fn task(
+		mut self,
+		name: impl Into<String>,
+		task_builder: TaskBuilder,
+	) -> Self {
+		self.unloaded_crate
+			.tasks
+			.insert(TaskName::new(name.into()), task_builder.build());
+		self
+	}
+
+	pub fn build(self) -> UnloadedCrate {
+		self.unloaded_crate
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskBuilder {
+	task: UnloadedTask,
+}
+
+impl TaskBuilder {
+	/// `deserialize_path` is where the task's configuration is read from, as in the RON format.
+	pub fn new(
+		type_name: impl Into<String>,
+		deserialize_path: impl Into<String>,
+	) -> Self {
+		Self {
+			task: UnloadedTask {
+				type_name: type_name.into(),
+				deserialize_path: deserialize_path.into(),
+				parents: vec![],
+				accesses: vec![],
+				entrypoint: false,
+				main_thread: false,
+			},
+		}
+	}
+
+	/// Start from an existing task, to change it.
+	pub fn from_task(task: UnloadedTask) -> Self {
+		Self { task }
+	}
+
+	pub fn parent(
+		mut self,
+		crate_name: impl Into<String>,
+		task_name: impl Into<String>,
+	) -> Self {
+		self.task
+			.parents
+			.push(FullTaskName::new(crate_name.into(), task_name.into()));
+		self
+	}
+
+	pub fn access(
+		mut self,
+		crate_name: impl Into<String>,
+		datachunk_name: impl Into<String>,
+		mut_immut: AccessType,
+	) -> Self {
+		self.task.accesses.push(Access {
+			of: FullDatachunkName::new(
+				crate_name.into(),
+				datachunk_name.into(),
+			),
+			mut_immut,
+			type_name: None,
+			partition: None,
+		});
+		self
+	}
+
+	/// Like [access](Self::access), but of one partition of the datachunk only.
+	pub fn partition_access(
+		mut self,
+		crate_name: impl Into<String>,
+		datachunk_name: impl Into<String>,
+		partition: impl Into<String>,
+		mut_immut: AccessType,
+	) -> Self {
+		self = self.access(crate_name, datachunk_name, mut_immut);
+		self.task.accesses.last_mut().unwrap().partition =
+			Some(partition.into());
+		self
+	}
+
+	/// Like [access](Self::access), but also check before anything runs that the datachunk is of type `type_name`.
+	pub fn typed_access(
+		mut self,
+		crate_name: impl Into<String>,
+		datachunk_name: impl Into<String>,
+		mut_immut: AccessType,
+		type_name: impl Into<String>,
+	) -> Self {
+		self = self.access(crate_name, datachunk_name, mut_immut);
+		self.task.accesses.last_mut().unwrap().type_name =
+			Some(type_name.into());
+		self
+	}
+
+	pub fn entrypoint(mut self, entrypoint: bool) -> Self {
+		self.task.entrypoint = entrypoint;
+		self
+	}
+
+	pub fn main_thread(mut self, main_thread: bool) -> Self {
+		self.task.main_thread = main_thread;
+		self
+	}
+
+	pub fn build(self) -> UnloadedTask {
+		self.task
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{CompositionBuilder, CrateBuilder, TaskBuilder};
+
+	use crate::{
+		composition::unloaded::{
+			unloaded_composition::UnloadedComposition,
+			unloaded_datachunk::UnloadedDatachunk,
+		},
+		concurrency::access::AccessType,
+		identify::task_name::FullTaskName,
+	};
+
+	const RON: &str = r#"(
+		crates: {
+			(name: "arm"): (
+				datachunks: {
+					(name: "pose"): (type_name: "Pose", deserialize_path: "pose.ron"),
+				},
+				tasks: {
+					(name: "tick"): (
+						type_name: "Tick",
+						deserialize_path: "tick.ron",
+						parents: [(
+							crate_name: (name: "arm"),
+							task_name: (name: "tick"),
+						)],
+						accesses: [(
+							of: (
+								crate_name: (name: "arm"),
+								datachunk_name: (name: "pose"),
+							),
+							mut_immut: MutableAccess,
+						)],
+						entrypoint: true,
+					),
+				},
+			),
+		},
+		children: [(name: "physics")],
+	)"#;
+
+	#[test]
+	fn builds_the_same_composition_as_ron() {
+		let parsed: UnloadedComposition = ron::from_str(RON).unwrap();
+		let built = CompositionBuilder::new()
+			.child("physics")
+			.with_crate(
+				"arm",
+				CrateBuilder::new()
+					.datachunk(
+						"pose",
+						UnloadedDatachunk::new("Pose", "pose.ron"),
+					)
+					.task(
+						"tick",
+						TaskBuilder::new("Tick", "tick.ron")
+							.entrypoint(true)
+							.parent("arm", "tick")
+							.access("arm", "pose", AccessType::MutableAccess),
+					),
+			)
+			.build();
+		assert_eq!(built.crates, parsed.crates);
+		assert_eq!(built.children, parsed.children);
+
+		let tick = FullTaskName::new("arm".to_owned(), "tick".to_owned());
+		let edited = CompositionBuilder::from_composition(built)
+			.without_task(&tick)
+			.build();
+		assert!(edited.get_unloaded_task(&tick).is_none());
+	}
+}
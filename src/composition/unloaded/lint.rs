@@ -0,0 +1,217 @@
+use crate::identify::{
+	datachunk_name::FullDatachunkName, task_name::FullTaskName,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt::{self, Display, Formatter};
+
+/// Something in a composition that is allowed, but probably not intended. See [LoadedComposition::lint](crate::composition::loaded::loaded_composition::LoadedComposition::lint).
+#[derive(
+	Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum LintKind {
+	/// A datachunk that no task accesses.
+	UnusedDatachunk,
+	/// A datachunk that only one task accesses, mutably, so nothing else ever sees what it writes.
+	UnreadMutation,
+	/// A parent that another of the task's parents already runs after within the same cycle.
+	RedundantParent,
+	/// A task that accesses no datachunks.
+	TaskWithoutAccesses,
+	/// An entrypoint that would run every cycle anyway, because other entrypoints reach it.
+	ReachableEntrypoint,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum LintSubject {
+	Task(FullTaskName),
+	Datachunk(FullDatachunkName),
+}
+
+/// Where a lint is allowed, listed under `allow_lints` in a composition:
+/// ```ignore
+/// allow_lints: {
+///     TaskWithoutAccesses: (all: true),
+///     UnreadMutation: (datachunks: [(crate_name: (name: "arm"), datachunk_name: (name: "rng"))]),
+/// },
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintAllow {
+	#[serde(default)]
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub all: bool,
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub tasks: Vec<FullTaskName>,
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub datachunks: Vec<FullDatachunkName>,
+}
+
+impl LintAllow {
+	pub fn allows(&self, subject: &LintSubject) -> bool {
+		self.all
+			|| match subject {
+				LintSubject::Task(name) => self.tasks.contains(name),
+				LintSubject::Datachunk(name) => self.datachunks.contains(name),
+			}
+	}
+
+	/// Combine with the allow list a child composition declared for the same lint.
+	pub(crate) fn merge(&mut self, other: LintAllow) {
+		self.all |= other.all;
+		self.tasks.extend(other.tasks);
+		self.datachunks.extend(other.datachunks);
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompositionLint {
+	pub kind: LintKind,
+	pub subject: LintSubject,
+	pub detail: String,
+}
+
+impl Display for LintSubject {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Task(name) => write!(f, "task {}", name),
+			Self::Datachunk(name) => write!(f, "datachunk {}", name),
+		}
+	}
+}
+
+impl Display for CompositionLint {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "{:?} {}: {}", self.kind, self.subject, self.detail)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{CompositionLint, LintAllow, LintKind, LintSubject};
+
+	use crate::{
+		composition::{
+			loaded::loaded_composition::LoadedComposition,
+			unloaded::{
+				composition_builder::{
+					CompositionBuilder, CrateBuilder, TaskBuilder,
+				},
+				unloaded_datachunk::UnloadedDatachunk,
+			},
+		},
+		concurrency::access::AccessType,
+		identify::{
+			datachunk_name::FullDatachunkName, task_name::FullTaskName,
+		},
+	};
+
+	#[test]
+	fn reports_each_lint_unless_allowed() {
+		let task = |name: &str| {
+			LintSubject::Task(FullTaskName::new(
+				"c".to_owned(),
+				name.to_owned(),
+			))
+		};
+		let datachunk = |name: &str| {
+			LintSubject::Datachunk(FullDatachunkName::new(
+				"c".to_owned(),
+				name.to_owned(),
+			))
+		};
+		let new_task =
+			|entrypoint| TaskBuilder::new("T", "t.ron").entrypoint(entrypoint);
+		let composition = CompositionBuilder::new()
+			.with_crate(
+				"c",
+				CrateBuilder::new()
+					.datachunk("shared", UnloadedDatachunk::new("D", "d.ron"))
+					.datachunk("state", UnloadedDatachunk::new("D", "d.ron"))
+					.datachunk("unused", UnloadedDatachunk::new("D", "d.ron"))
+					.task(
+						"e",
+						new_task(true).parent("c", "c").access(
+							"c",
+							"shared",
+							AccessType::ImmutableAccess,
+						),
+					)
+					.task(
+						"a",
+						new_task(false)
+							.parent("c", "e")
+							.access("c", "state", AccessType::MutableAccess)
+							.access("c", "shared", AccessType::MutableAccess),
+					)
+					.task(
+						"b",
+						new_task(false).parent("c", "a").access(
+							"c",
+							"shared",
+							AccessType::ImmutableAccess,
+						),
+					)
+					.task(
+						"c",
+						new_task(false).parent("c", "b").parent("c", "a"),
+					)
+					.task(
+						"f",
+						new_task(true).parent("c", "e").access(
+							"c",
+							"shared",
+							AccessType::ImmutableAccess,
+						),
+					),
+			)
+			.allow_lint(
+				LintKind::TaskWithoutAccesses,
+				LintAllow {
+					tasks: vec![FullTaskName::new(
+						"c".to_owned(),
+						"c".to_owned(),
+					)],
+					..Default::default()
+				},
+			)
+			.build();
+
+		let mut found: Vec<_> = LoadedComposition::lint(&composition)
+			.into_iter()
+			.map(|lint| (lint.kind, lint.subject))
+			.collect();
+		found.sort();
+		assert_eq!(
+			found,
+			vec![
+				(LintKind::UnusedDatachunk, datachunk("unused")),
+				(LintKind::UnreadMutation, datachunk("state")),
+				(LintKind::RedundantParent, task("c")),
+				(LintKind::ReachableEntrypoint, task("f")),
+			]
+		);
+	}
+
+	#[test]
+	fn lints_name_subjects_as_paths() {
+		let lint = CompositionLint {
+			kind: LintKind::RedundantParent,
+			subject: LintSubject::Task(FullTaskName::new(
+				"arm".to_owned(),
+				"tick".to_owned(),
+			)),
+			detail: format!(
+				"{} is listed twice",
+				FullTaskName::new("arm".to_owned(), "read".to_owned())
+			),
+		};
+		assert_eq!(
+			lint.to_string(),
+			"RedundantParent task arm/tick: arm/read is listed twice"
+		);
+	}
+}
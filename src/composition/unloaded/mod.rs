@@ -1,3 +1,6 @@
+pub mod composition_builder;
+pub mod lint;
+pub mod task_edits;
 pub mod unloaded_composition;
 pub mod unloaded_crate;
 pub mod unloaded_datachunk;
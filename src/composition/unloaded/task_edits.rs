@@ -0,0 +1,29 @@
+use crate::{
+	composition::unloaded::unloaded_task::UnloadedTask,
+	identify::task_name::FullTaskName,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A batch of task instances to add to or remove from a running composition. Added tasks must use task types that their crate's library already exports, and removed tasks must not be parents of any task that remains. Removals are applied before additions, so a task can be replaced by naming it in both. Edits are applied at the next cycle boundary, reusing every loaded task, datachunk and library that is not removed.
+#[derive(Clone, Debug, Default)]
+pub struct TaskEdits {
+	pub add: BTreeMap<FullTaskName, UnloadedTask>,
+	pub remove: BTreeSet<FullTaskName>,
+}
+
+impl TaskEdits {
+	pub fn is_empty(&self) -> bool {
+		self.add.is_empty() && self.remove.is_empty()
+	}
+
+	/// Combine the edits of several tasks returned in the same cycle. If both add a task under the same name, `other` wins.
+	pub fn merge(&mut self, other: &Self) {
+		for (task_name, unloaded_task) in &other.add {
+			self.add.insert(task_name.clone(), unloaded_task.clone());
+		}
+		for task_name in &other.remove {
+			self.remove.insert(task_name.clone());
+		}
+	}
+}
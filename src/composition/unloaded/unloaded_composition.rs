@@ -1,25 +1,45 @@
 use crate::{
 	composition::unloaded::{
-		unloaded_crate::UnloadedCrate, unloaded_task::UnloadedTask,
+		lint::{LintAllow, LintKind},
+		task_edits::TaskEdits,
+		unloaded_crate::UnloadedCrate,
+		unloaded_task::UnloadedTask,
 	},
+	concurrency::happens_before::HappensBefore,
 	dylib_management::safe_library::{
 		core_library::CoreLibrary,
-		safe_library::{DebugMode, LibraryRecompile, SafeLibrary},
+		safe_library::{self, DebugMode, LibraryRecompile, SafeLibrary},
 	},
-	errors::parse_errors::{
-		custard_composition_cycle_error::CustardCompositionCycleError,
-		custard_ron_parse_error::CustardRonCompositionParseError,
+	errors::{
+		custard_error::CustardError,
+		load_errors::custard_crate_version_mismatch_error::{
+			CrateVersionOrigin, CustardCrateVersionMismatchError,
+		},
+		parse_errors::{
+			custard_composition_cycle_error::CustardCompositionCycleError,
+			custard_ron_parse_error::CustardRonCompositionParseError,
+		},
+		task_composition_errors::{
+			custard_crate_not_found_error::CustardCrateNotFoundError,
+			custard_task_already_exists_error::CustardTaskAlreadyExistsError,
+			custard_task_not_found_error::CustardTaskNotFoundError,
+			custard_task_still_required_error::CustardTaskStillRequiredError,
+		},
+	},
+	identify::{
+		crate_name::CrateName, custard_name::CustardName,
+		task_name::FullTaskName,
 	},
-	identify::{crate_name::CrateName, task_name::FullTaskName},
 };
 
-use ron;
-use serde::Deserialize;
+use cargo_metadata::{MetadataCommand, Version, VersionReq};
+use log::info;
+use ron::{self, ser::PrettyConfig};
+use serde::{Deserialize, Serialize};
 
 use std::{
 	cell::RefCell,
 	collections::{BTreeMap, BTreeSet},
-	error::Error,
 	rc::Rc,
 };
 
@@ -28,10 +48,24 @@ const ENVIRONMENT_VAR_STR_ALLOW_DEPENDENCY_CYCLES: &'static str =
 	"CUSTARD_ALLOW_DEPENDENCY_CYCLES";
 
 /// Stores the fundamental information about a composition before user crates are dynamically loaded.
-#[derive(Debug, Deserialize)]
+///
+/// Unknown fields are rejected, here and in every struct a composition is made of, rather than silently ignored: a composition that only loaded because a misspelled field was dropped now fails to parse, with a hint naming the field it was probably meant to be.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UnloadedComposition {
 	pub(crate) crates: BTreeMap<CrateName, UnloadedCrate>,
-	children: Vec<CrateName>,
+	pub(crate) children: Vec<CrateName>,
+	/// Semver requirements for some of `children`, checked against both the package version in the child's `Cargo.toml` and the version embedded in its core library.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub(crate) child_versions: BTreeMap<CrateName, VersionReq>,
+	/// Lints not to report, for the tasks and datachunks listed. The lists of children are combined with these.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub(crate) allow_lints: BTreeMap<LintKind, LintAllow>,
+	/// Crates compiled while loading this composition, which need not be compiled again to load their user libraries.
+	#[serde(skip)]
+	pub(crate) built: BTreeSet<CrateName>,
 }
 
 impl UnloadedComposition {
@@ -58,49 +92,110 @@ impl UnloadedComposition {
 		recompile: LibraryRecompile,
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
+		Self::parse(to_deserialize)?.load_children(recompile, debug, drop_list)
+	}
+
+	/// Parse a composition without loading its children, so no libraries are loaded. Useful for tools that only need to read or reformat a composition file; see [to_ron](Self::to_ron). Fields the composition does not know are an error.
+	pub fn parse(to_deserialize: String) -> Result<Self, CustardError> {
 		let res: Result<UnloadedComposition, ron::Error> =
 			ron::from_str(to_deserialize.as_str());
-		let mut to_return = match res {
-			Ok(v) => v,
-			Err(error) => {
-				return Err(Box::new(CustardRonCompositionParseError {
-					error,
-					relevant_ron: to_deserialize,
-				}))
+		match res {
+			Ok(v) => Ok(v),
+			Err(error) => Err(CustardRonCompositionParseError {
+				error,
+				origin: None,
+				relevant_ron: to_deserialize,
 			}
-		};
+			.into()),
+		}
+	}
+
+	/// Write this composition as pretty RON that [parse](Self::parse) reads back unchanged. For a composition returned by [from_string](Self::from_string) this is the effective composition: the crates declared by every child are included, `children` lists the children of children as well, and `child_versions` holds their version requirements too, combined where more than one composition constrains the same crate. Fields left at their defaults are omitted.
+	pub fn to_ron(&self) -> Result<String, CustardError> {
+		let config = PrettyConfig::new().with_indentor("\t".to_owned());
+		ron::ser::to_string_pretty(self, config)
+			.map_err(|e| CustardError::RonSerialize(Box::new(e)))
+	}
+
+	/// Load the core libraries of `children`, and of their children in turn, adding the crates they declare. This is the second half of [from_string](Self::from_string), for compositions put together with a [CompositionBuilder](crate::composition::unloaded::composition_builder::CompositionBuilder).
+	///
+	/// # Safety
+	///
+	/// The loaded libraries are pushed onto `drop_list`, and the returned composition, along with anything taken from it, must be dropped before `drop_list` is. See [from_string](Self::from_string).
+	pub unsafe fn load_children(
+		self,
+		recompile: LibraryRecompile,
+		debug: DebugMode,
+		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
+	) -> Result<Self, CustardError> {
+		let mut to_return = self;
 
 		let mut traversal_tree =
 			BTreeMap::<Option<CrateName>, Vec<CrateName>>::new();
 
 		traversal_tree.insert(None, to_return.children.clone());
 
-		let mut should_break = false;
+		let mut child_versions = ChildVersions::default();
+		for (child, required) in &to_return.child_versions {
+			child_versions.require(None, child, required)?;
+		}
+
+		//load the crate tree one level at a time, so that each level is compiled in a single cargo invocation
+		loop {
+			let mut pending: Vec<CrateName> = vec![];
+			for child in &to_return.children {
+				if !traversal_tree.contains_key(&Some(child.clone()))
+					&& !pending.contains(child)
+				{
+					pending.push(child.clone());
+				}
+			}
+			if pending.is_empty() {
+				break;
+			}
 
-		while !should_break {
-			for child_i in 0..to_return.children.len() {
-				should_break = false;
+			to_return.built.append(&mut safe_library::compile_libraries(
+				&pending,
+				&recompile,
+				debug.clone(),
+			)?);
 
-				if traversal_tree
-					.contains_key(&Some(to_return.children[child_i].clone()))
+			for child in pending {
+				let (mut child_composition, library_version) =
+					Self::from_crate(
+						child.clone(),
+						LibraryRecompile::InsistCached,
+						debug.clone(),
+						drop_list.clone(),
+					)?;
+				child_versions.loaded(&child, library_version)?;
+				for (grandchild, required) in &child_composition.child_versions
 				{
-					should_break = true;
-					continue;
+					child_versions.require(
+						Some(&child),
+						grandchild,
+						required,
+					)?;
+					//kept in the effective composition, where a crate required by several children must meet every requirement
+					let effective = to_return
+						.child_versions
+						.entry(grandchild.clone())
+						.or_insert(VersionReq::STAR);
+					for comparator in &required.comparators {
+						if !effective.comparators.contains(comparator) {
+							effective.comparators.push(comparator.clone());
+						}
+					}
 				}
-				let mut child_composition = Self::from_crate(
-					to_return.children[child_i].clone(),
-					recompile.clone(),
-					debug.clone(),
-					drop_list.clone(),
-				)?;
-
-				traversal_tree.insert(
-					Some(to_return.children[child_i].clone()),
-					child_composition.children.clone(),
-				);
+
+				traversal_tree
+					.insert(Some(child), child_composition.children.clone());
 
 				to_return.crates.append(&mut child_composition.crates);
+				for (kind, allow) in child_composition.allow_lints {
+					to_return.allow_lints.entry(kind).or_default().merge(allow);
+				}
 				to_return.children.append(&mut child_composition.children); //mutable access, order relative to traversal_tree insert
 			}
 		}
@@ -122,66 +217,124 @@ impl UnloadedComposition {
 		Ok(to_return)
 	}
 
-	/// Determine if two tasks are unsynchronized. This is determined by doing a search of their ancestors and seeing if they reach a common ancestor (in which case they are unsynchronized) before each other (in which case they must be synchronized).
-	pub fn are_tasks_unsynchronized(
+	/// Create a copy of this composition with `edits` applied. Removals are applied first. Only the shape of the graph is validated here; use [LoadedComposition::check_task_edits](crate::composition::loaded::loaded_composition::LoadedComposition::check_task_edits) to check accesses, cycles and task types.
+	pub fn with_task_edits(
 		&self,
-		task_name: FullTaskName,
-		other_task_name: FullTaskName,
-	) -> bool {
-		let mut first_ab = true;
-		let mut found_ab = false;
-
-		let mut traversal_list = vec![];
-
-		self.traverse_until(
-			&task_name.clone(),
-			&mut traversal_list,
-			Rc::new(RefCell::new(|current, _contents| -> bool {
-				if !first_ab && current == &other_task_name {
-					found_ab = true;
-					return true;
+		edits: &TaskEdits,
+	) -> Result<Self, CustardError> {
+		let mut ret = self.clone();
+
+		for task_name in &edits.remove {
+			let removed = ret.crates.get_mut(&task_name.crate_name).and_then(
+				|unloaded_crate| {
+					unloaded_crate.tasks.remove(&task_name.task_name)
+				},
+			);
+			if removed.is_none() {
+				return Err(CustardTaskNotFoundError {
+					offending_task: task_name.clone(),
 				}
-				first_ab = false;
-				return false;
-			})),
-		);
+				.into());
+			}
+		}
 
-		if !found_ab {
-			return true;
+		for (task_name, unloaded_task) in &edits.add {
+			let unloaded_crate = match ret.crates.get_mut(&task_name.crate_name)
+			{
+				Some(v) => v,
+				None => {
+					return Err(CustardCrateNotFoundError {
+						offending_crate: task_name.crate_name.clone(),
+						required_by: task_name.clone(),
+					}
+					.into())
+				}
+			};
+			if unloaded_crate.tasks.contains_key(&task_name.task_name) {
+				return Err(CustardTaskAlreadyExistsError {
+					offending_task: task_name.clone(),
+				}
+				.into());
+			}
+			unloaded_crate
+				.tasks
+				.insert(task_name.task_name.clone(), unloaded_task.clone());
 		}
 
-		traversal_list.remove(0); //We want to find task_name again. We don't need to remove other_task_name from the list because it doesn't exist within it
+		for (crate_name, unloaded_crate) in &ret.crates {
+			for (task_name, unloaded_task) in &unloaded_crate.tasks {
+				for parent in &unloaded_task.parents {
+					if ret.get_unloaded_task(parent).is_none() {
+						let required_by = FullTaskName {
+							crate_name: crate_name.clone(),
+							task_name: task_name.clone(),
+						};
+						if edits.remove.contains(parent) {
+							return Err(CustardTaskStillRequiredError {
+								offending_task: parent.clone(),
+								required_by,
+							}
+							.into());
+						}
+						return Err(CustardTaskNotFoundError {
+							offending_task: parent.clone(),
+						}
+						.into());
+					}
+				}
+			}
+		}
 
-		let mut first_ba = true;
-		let mut found_ba = false;
+		Ok(ret)
+	}
 
-		self.traverse_until(
-			&other_task_name.clone(),
-			&mut traversal_list,
-			Rc::new(RefCell::new(|current, _contents| -> bool {
-				if !first_ba && current == &task_name {
-					found_ba = true;
-					return true;
-				}
-				first_ba = false;
-				return false;
-			})),
-		);
+	/// The crates of this composition that a partial reload to `new` has to load again: those in `reload_for_sure`, those whose entry changed, and every instance of a library that one of `reload_for_sure` is loaded from, so that no instance keeps running tasks from the old copy of a reloaded library.
+	pub fn crates_to_reload(
+		&self,
+		new: &UnloadedComposition,
+		reload_for_sure: &BTreeSet<CrateName>,
+	) -> BTreeSet<CrateName> {
+		let reloaded_libraries: BTreeSet<&CrateName> = reload_for_sure
+			.iter()
+			.filter_map(|crate_name| {
+				self.crates
+					.get(crate_name)
+					.map(|v| v.library_name(crate_name))
+			})
+			.collect();
+		self.crates
+			.iter()
+			.filter(|(crate_name, unloaded_crate)| {
+				reloaded_libraries
+					.contains(unloaded_crate.library_name(crate_name))
+					|| new.crates.get(*crate_name) != Some(*unloaded_crate)
+			})
+			.map(|(crate_name, _)| crate_name.clone())
+			.collect()
+	}
 
-		!(found_ab && found_ba)
+	/// Determine if two tasks are unsynchronized, that is whether neither is ordered before the other within a cycle. To ask this of many pairs, build a [HappensBefore] once instead.
+	pub fn are_tasks_unsynchronized(
+		&self,
+		task_name: FullTaskName,
+		other_task_name: FullTaskName,
+	) -> bool {
+		HappensBefore::new(self)
+			.are_tasks_unsynchronized(&task_name, &other_task_name)
 	}
 
 	fn recurse_crate_traversal_tree(
 		traversal_tree: &BTreeMap<Option<CrateName>, Vec<CrateName>>,
 		traversal_tree_traversal_list: &mut Vec<Option<CrateName>>,
 		active_node: Option<CrateName>,
-	) -> Result<(), Box<dyn Error>> {
+	) -> Result<(), CustardError> {
 		traversal_tree_traversal_list.push(active_node.clone());
 		for subnode in traversal_tree.get(&active_node).unwrap() {
 			if traversal_tree_traversal_list.contains(&Some(subnode.clone())) {
-				return Err(Box::new(CustardCompositionCycleError {
+				return Err(CustardCompositionCycleError {
 					offending_crate: active_node.clone(),
-				}));
+				}
+				.into());
 			}
 			Self::recurse_crate_traversal_tree(
 				traversal_tree,
@@ -198,11 +351,17 @@ impl UnloadedComposition {
 		recompile: LibraryRecompile,
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<(Self, Option<Version>), CustardError> {
 		let loaded =
 			Rc::new(CoreLibrary::new(crate_name, recompile, debug, drop_list)?);
 		let composition_string =
-			((loaded.symbols.as_ref().unwrap().composition)()).into_rust()?;
+			((loaded.symbols.as_ref().unwrap().composition)())
+				.into_rust()
+				.map_err(|e| CustardError::CoreLibraryFunction {
+					crate_name: loaded.get_crate_name().clone(),
+					function: "__custard_composition__",
+					source: e,
+				})?;
 		let res: Result<UnloadedComposition, ron::Error> =
 			ron::from_str(composition_string.as_str());
 
@@ -211,13 +370,15 @@ impl UnloadedComposition {
 				for (_, crate_contents) in &mut v.crates {
 					crate_contents.lib = Some(loaded.clone());
 				}
-				Ok(v)
+				Ok((v, loaded.embedded_version()))
 			}
 			Err(error) => {
-				return Err(Box::new(CustardRonCompositionParseError {
+				return Err(CustardRonCompositionParseError {
 					error,
+					origin: Some(loaded.get_crate_name().clone()),
 					relevant_ron: composition_string,
-				}))
+				}
+				.into())
 			}
 		};
 	}
@@ -319,3 +480,291 @@ impl UnloadedComposition {
 		return false;
 	}
 }
+
+/// Checks `child_versions` while the crate tree is loaded, so that a mismatch stops loading early: each requirement is checked against the child's `Cargo.toml` as soon as it is declared, before the child is compiled, and against the version embedded in the child's core library as soon as that is loaded.
+#[derive(Default)]
+struct ChildVersions {
+	/// Package versions by name, with `-` read as `_`. Only read once a requirement needs them, as this runs `cargo metadata`.
+	manifest_versions: Option<BTreeMap<String, Version>>,
+	/// The versions embedded in the core libraries loaded so far.
+	library_versions: BTreeMap<CrateName, Option<Version>>,
+	requirements: Vec<(Option<CrateName>, CrateName, VersionReq)>,
+}
+
+impl ChildVersions {
+	fn require(
+		&mut self,
+		required_by: Option<&CrateName>,
+		child: &CrateName,
+		required: &VersionReq,
+	) -> Result<(), CustardError> {
+		if self.manifest_versions.is_none() {
+			info!("Reading workspace metadata for child crate versions.");
+			let metadata =
+				MetadataCommand::new().no_deps().exec().map_err(|e| {
+					CustardError::Metadata {
+						source: Box::new(e),
+					}
+				})?;
+			self.manifest_versions = Some(
+				metadata
+					.packages
+					.into_iter()
+					.map(|v| (v.name.replace("-", "_"), v.version))
+					.collect(),
+			);
+		}
+		let manifest_version = self
+			.manifest_versions
+			.as_ref()
+			.unwrap()
+			.get(&child.get().replace("-", "_"))
+			.cloned();
+		Self::check(
+			required_by,
+			child,
+			required,
+			CrateVersionOrigin::Manifest,
+			manifest_version,
+		)?;
+
+		//a crate required by more than one composition may already be loaded
+		if let Some(library_version) = self.library_versions.get(child) {
+			Self::check(
+				required_by,
+				child,
+				required,
+				CrateVersionOrigin::Library,
+				library_version.clone(),
+			)?;
+		}
+
+		self.requirements.push((
+			required_by.cloned(),
+			child.clone(),
+			required.clone(),
+		));
+		Ok(())
+	}
+
+	/// Check the requirements declared so far on `child` against the version embedded in its core library, which was just loaded.
+	fn loaded(
+		&mut self,
+		child: &CrateName,
+		library_version: Option<Version>,
+	) -> Result<(), CustardError> {
+		for (required_by, _, required) in
+			self.requirements.iter().filter(|v| &v.1 == child)
+		{
+			Self::check(
+				required_by.as_ref(),
+				child,
+				required,
+				CrateVersionOrigin::Library,
+				library_version.clone(),
+			)?;
+		}
+		self.library_versions.insert(child.clone(), library_version);
+		Ok(())
+	}
+
+	fn check(
+		required_by: Option<&CrateName>,
+		child: &CrateName,
+		required: &VersionReq,
+		found_in: CrateVersionOrigin,
+		found: Option<Version>,
+	) -> Result<(), CustardError> {
+		match &found {
+			Some(v) if required.matches(v) => Ok(()),
+			_ => Err(CustardCrateVersionMismatchError {
+				offending_crate: child.clone(),
+				required_by: required_by.cloned(),
+				required: required.clone(),
+				found_in,
+				found,
+			}
+			.into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ChildVersions, UnloadedComposition};
+
+	use crate::{
+		composition::unloaded::{
+			composition_builder::{
+				CompositionBuilder, CrateBuilder, TaskBuilder,
+			},
+			task_edits::TaskEdits,
+			unloaded_datachunk::UnloadedDatachunk,
+		},
+		concurrency::access::AccessType,
+		errors::{
+			custard_error::CustardError,
+			load_errors::custard_crate_version_mismatch_error::CrateVersionOrigin,
+		},
+		identify::{
+			crate_name::CrateName, custard_name::CustardName,
+			task_name::FullTaskName,
+		},
+	};
+
+	use cargo_metadata::{Version, VersionReq};
+
+	use std::collections::{BTreeMap, BTreeSet};
+
+	#[test]
+	fn ron_round_trip() {
+		let composition = CompositionBuilder::new()
+			.child("physics")
+			.child_version("physics", VersionReq::parse("^0.2").unwrap())
+			.with_crate(
+				"left_arm",
+				CrateBuilder::new()
+					.instance_of("arm")
+					.datachunk(
+						"pose",
+						UnloadedDatachunk::new("Pose", "pose.ron"),
+					)
+					.task(
+						"tick",
+						TaskBuilder::new("Tick", "tick.ron")
+							.entrypoint(true)
+							.main_thread(true)
+							.parent("left_arm", "tick")
+							.typed_access(
+								"left_arm",
+								"pose",
+								AccessType::MutableAccess,
+								"Pose",
+							),
+					),
+			)
+			.build();
+
+		let written = composition.to_ron().unwrap();
+		let read = UnloadedComposition::parse(written.clone()).unwrap();
+		assert_eq!(read.crates, composition.crates);
+		assert_eq!(read.children, composition.children);
+		assert_eq!(read.child_versions, composition.child_versions);
+		assert_eq!(read.to_ron().unwrap(), written);
+	}
+
+	#[test]
+	fn task_edits_name_what_is_missing() {
+		let composition = CompositionBuilder::new()
+			.with_task(
+				FullTaskName::new("c".to_owned(), "a".to_owned()),
+				TaskBuilder::new("T", "t.ron")
+					.entrypoint(true)
+					.parent("c", "a"),
+			)
+			.build();
+		let added = TaskBuilder::new("T", "t.ron").parent("c", "a").build();
+
+		let mut edits = TaskEdits::default();
+		edits
+			.add
+			.insert(FullTaskName::new("d".to_owned(), "b".to_owned()), added);
+		assert!(matches!(
+			composition.with_task_edits(&edits),
+			Err(CustardError::CrateNotFound(_))
+		));
+
+		let mut edits = TaskEdits::default();
+		edits
+			.remove
+			.insert(FullTaskName::new("c".to_owned(), "b".to_owned()));
+		assert!(matches!(
+			composition.with_task_edits(&edits),
+			Err(CustardError::TaskNotFound(_))
+		));
+	}
+
+	#[test]
+	fn reloading_an_instance_reloads_its_library() {
+		let composition = CompositionBuilder::new()
+			.with_crate("arm", CrateBuilder::new())
+			.with_crate("left_arm", CrateBuilder::new().instance_of("arm"))
+			.with_crate("right_arm", CrateBuilder::new().instance_of("arm"))
+			.with_crate("physics", CrateBuilder::new())
+			.build();
+		let name = |v: &str| CrateName::new(v.to_owned());
+
+		let reloaded = composition.crates_to_reload(
+			&composition,
+			&BTreeSet::from([name("left_arm")]),
+		);
+		assert_eq!(
+			reloaded,
+			BTreeSet::from([name("arm"), name("left_arm"), name("right_arm")])
+		);
+
+		let mut changed = composition.clone();
+		changed
+			.crates
+			.get_mut(&name("physics"))
+			.unwrap()
+			.instance_of = Some(name("arm"));
+		assert_eq!(
+			composition.crates_to_reload(&changed, &BTreeSet::new()),
+			BTreeSet::from([name("physics")])
+		);
+	}
+
+	#[test]
+	fn child_versions_are_checked_against_manifest_and_library() {
+		let found_in = |result: Result<(), CustardError>| match result {
+			Err(CustardError::CrateVersionMismatch(e)) => Some(e.found_in),
+			Err(e) => panic!("unexpected error: {}", e),
+			Ok(()) => None,
+		};
+		let versions = || ChildVersions {
+			manifest_versions: Some(BTreeMap::from([(
+				"physics_engine".to_owned(),
+				Version::new(1, 2, 0),
+			)])),
+			..Default::default()
+		};
+		let physics = CrateName::new("physics-engine".to_owned());
+		let required = VersionReq::parse("^1.1").unwrap();
+
+		let mut child_versions = versions();
+		assert_eq!(
+			found_in(child_versions.require(
+				None,
+				&physics,
+				&VersionReq::parse("^2").unwrap()
+			)),
+			Some(CrateVersionOrigin::Manifest)
+		);
+
+		//a stale library is caught once it is loaded
+		let mut child_versions = versions();
+		assert_eq!(
+			found_in(child_versions.require(None, &physics, &required)),
+			None
+		);
+		assert_eq!(
+			found_in(
+				child_versions.loaded(&physics, Some(Version::new(1, 0, 3)))
+			),
+			Some(CrateVersionOrigin::Library)
+		);
+
+		//and so is a requirement declared after the library was loaded
+		let mut child_versions = versions();
+		assert_eq!(found_in(child_versions.loaded(&physics, None)), None);
+		assert_eq!(
+			found_in(child_versions.require(
+				Some(&CrateName::new("arm".to_owned())),
+				&physics,
+				&required
+			)),
+			Some(CrateVersionOrigin::Library)
+		);
+	}
+}
@@ -3,17 +3,25 @@ use crate::{
 		unloaded_datachunk::UnloadedDatachunk, unloaded_task::UnloadedTask,
 	},
 	dylib_management::safe_library::core_library::CoreLibrary,
-	identify::{datachunk_name::DatachunkName, task_name::TaskName},
+	identify::{
+		crate_name::CrateName, datachunk_name::DatachunkName,
+		task_name::TaskName,
+	},
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use std::{collections::BTreeMap, rc::Rc};
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UnloadedCrate {
 	pub(crate) datachunks: BTreeMap<DatachunkName, UnloadedDatachunk>,
 	pub(crate) tasks: BTreeMap<TaskName, UnloadedTask>,
+	/// Load this entry's tasks and datachunks from another crate's library, so that one crate can be instantiated several times under different names. Each instance has its own tasks and datachunks, but all instances share one copy of the library.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) instance_of: Option<CrateName>,
 	#[serde(skip)]
 	#[serde(default)]
 	pub(crate) lib: Option<Rc<CoreLibrary<'static>>>,
@@ -21,6 +29,15 @@ pub struct UnloadedCrate {
 
 impl PartialEq for UnloadedCrate {
 	fn eq(&self, other: &Self) -> bool {
-		self.datachunks == other.datachunks && self.tasks == other.tasks
+		self.datachunks == other.datachunks
+			&& self.tasks == other.tasks
+			&& self.instance_of == other.instance_of
+	}
+}
+
+impl UnloadedCrate {
+	/// The crate whose library this entry is loaded from, given the name it is listed under.
+	pub fn library_name<'a>(&'a self, name: &'a CrateName) -> &'a CrateName {
+		self.instance_of.as_ref().unwrap_or(name)
 	}
 }
@@ -1,11 +1,34 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use std::fmt::{Debug, Formatter};
 
-#[derive(Deserialize, PartialEq)]
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UnloadedDatachunk {
 	pub type_name: String,
 	pub deserialize_path: String,
+	/// Names of disjoint parts of the datachunk, which tasks can access separately. The datachunk type must implement [Partitionable](crate::user_types::datachunk::Partitionable) for them.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub partitions: Vec<String>,
+}
+
+impl UnloadedDatachunk {
+	pub fn new(
+		type_name: impl Into<String>,
+		deserialize_path: impl Into<String>,
+	) -> Self {
+		Self {
+			type_name: type_name.into(),
+			deserialize_path: deserialize_path.into(),
+			partitions: vec![],
+		}
+	}
+
+	pub fn partition(mut self, name: impl Into<String>) -> Self {
+		self.partitions.push(name.into());
+		self
+	}
 }
 
 impl Debug for UnloadedDatachunk {
@@ -13,6 +36,7 @@ impl Debug for UnloadedDatachunk {
 		f.write_str("\n(field \"deserialize\" omitted)\n")?;
 		f.debug_struct("UnloadedDatachunk")
 			.field("type_name", &self.type_name)
+			.field("partitions", &self.partitions)
 			.finish()
 	}
 }
@@ -1,8 +1,9 @@
 use crate::{concurrency::access::Access, identify::task_name::FullTaskName};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct UnloadedTask {
 	pub type_name: String,
 	pub deserialize_path: String,
@@ -10,4 +11,8 @@ pub struct UnloadedTask {
 	pub parents: Vec<FullTaskName>,
 	pub accesses: Vec<Access>,
 	pub entrypoint: bool,
+	/// Run this task on the thread that called `CustardInstance::run`, for libraries that must be called from the thread that created them.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub main_thread: bool,
 }
@@ -1,14 +1,40 @@
 use crate::identify::datachunk_name::FullDatachunkName;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct Access {
 	pub of: FullDatachunkName,
 	pub mut_immut: AccessType,
+	/// The datachunk's `type_name`, if the task wants it checked before anything runs.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub type_name: Option<String>,
+	/// One of the datachunk's `partitions`, to access only that partition. Tasks may mutate different partitions of a datachunk at the same time.
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub partition: Option<String>,
 }
 
-#[derive(PartialEq, Clone, Copy, Debug, Deserialize)]
+impl Access {
+	/// Whether two tasks that may run at the same time cannot both have these accesses.
+	pub fn conflicts_with(&self, other: &Self) -> bool {
+		if self.of != other.of {
+			return false;
+		}
+		if let (Some(partition), Some(other_partition)) =
+			(&self.partition, &other.partition)
+		{
+			if partition != other_partition {
+				return false;
+			}
+		}
+		!self.mut_immut.commensurable(&other.mut_immut)
+	}
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Deserialize, Serialize)]
 pub enum AccessType {
 	ImmutableAccess,
 	MutableAccess,
@@ -0,0 +1,205 @@
+use crate::utils::panic_capture;
+
+use log::info;
+
+use std::{
+	future::Future,
+	panic::AssertUnwindSafe,
+	pin::Pin,
+	sync::{
+		mpsc::{self, Sender},
+		Arc, Mutex, Weak,
+	},
+	task::{Context, Poll, Wake, Waker},
+	thread::{self, JoinHandle},
+};
+
+/// Polls the future, or cancels it when passed `None`.
+type PollFn = Box<dyn FnMut(Option<&mut Context>) -> Poll<()> + Send>;
+
+/// A future together with what to do once it resolves. Waking it puts it back on the executor's queue.
+struct ExecutorTask {
+	poll: Mutex<Option<PollFn>>,
+	queue: Mutex<Sender<Option<Arc<ExecutorTask>>>>,
+}
+
+impl Wake for ExecutorTask {
+	fn wake(self: Arc<Self>) {
+		let queue = self.queue.lock().unwrap().clone();
+		//the executor may already have shut down, in which case there is nothing left to wake
+		let _ = queue.send(Some(self));
+	}
+}
+
+/// Drives the futures of async tasks on a single dedicated thread. Owned by the instance, and shut down when dropped, which also drops any futures that have not resolved.
+#[derive(Debug)]
+pub struct Executor {
+	queue: Mutex<Sender<Option<Arc<ExecutorTask>>>>,
+	/// Every spawned task that may not have resolved, for [cancel_pending](Self::cancel_pending).
+	spawned: Mutex<Vec<Weak<ExecutorTask>>>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl Executor {
+	pub fn new() -> Self {
+		let (sender, receiver) = mpsc::channel::<Option<Arc<ExecutorTask>>>();
+		let thread = thread::Builder::new()
+			.name("custard-executor".to_owned())
+			.spawn(move || {
+				//`None` is sent on drop
+				while let Ok(Some(task)) = receiver.recv() {
+					let mut poll = task.poll.lock().unwrap();
+					let finished = match poll.as_mut() {
+						Some(v) => {
+							let waker = Waker::from(task.clone());
+							let mut context = Context::from_waker(&waker);
+							v(Some(&mut context)).is_ready()
+						}
+						None => continue, //woken after it already resolved
+					};
+					if finished {
+						*poll = None;
+					}
+				}
+				info!("Executor shut down.");
+			})
+			.unwrap();
+		Self {
+			queue: Mutex::new(sender),
+			spawned: Mutex::new(vec![]),
+			thread: Some(thread),
+		}
+	}
+
+	/// Drive `future` to completion, then call `on_complete` on the executor thread with its output, or with the payload if polling it panicked. If the future is cancelled by [cancel_pending](Self::cancel_pending) instead, `on_complete` is called with `None` on the cancelling thread.
+	pub fn spawn<T: 'static>(
+		&self,
+		mut future: Pin<Box<dyn Future<Output = T> + Send>>,
+		on_complete: impl FnOnce(Option<thread::Result<T>>) + Send + 'static,
+	) {
+		let mut on_complete = Some(on_complete);
+		let poll: PollFn = Box::new(move |context| {
+			let context = match context {
+				Some(v) => v,
+				None => {
+					(on_complete.take().unwrap())(None);
+					return Poll::Ready(());
+				}
+			};
+			let result =
+				match panic_capture::catch_unwind(AssertUnwindSafe(|| {
+					future.as_mut().poll(context)
+				})) {
+					Ok(Poll::Pending) => return Poll::Pending,
+					Ok(Poll::Ready(v)) => Ok(v),
+					Err(e) => Err(e),
+				};
+			(on_complete.take().unwrap())(Some(result));
+			Poll::Ready(())
+		});
+		let queue = self.queue.lock().unwrap().clone();
+		let task = Arc::new(ExecutorTask {
+			poll: Mutex::new(Some(poll)),
+			queue: Mutex::new(queue.clone()),
+		});
+		let mut spawned = self.spawned.lock().unwrap();
+		spawned.retain(|v| v.strong_count() > 0);
+		spawned.push(Arc::downgrade(&task));
+		drop(spawned);
+		queue.send(Some(task)).unwrap();
+	}
+
+	/// Drop every future that has not resolved, calling its `on_complete` with `None`. Once this returns, no completion of a future spawned before the call is running or will run.
+	pub fn cancel_pending(&self) {
+		let spawned = std::mem::take(&mut *self.spawned.lock().unwrap());
+		for task in spawned.iter().filter_map(Weak::upgrade) {
+			//waits for a poll in progress on the executor thread
+			let mut poll = task.poll.lock().unwrap();
+			if let Some(mut v) = poll.take() {
+				let _ = v(None);
+			}
+		}
+	}
+}
+
+impl Default for Executor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Drop for Executor {
+	fn drop(&mut self) {
+		let _ = self.queue.lock().unwrap().send(None);
+		if let Some(thread) = self.thread.take() {
+			//the last reference can be dropped by a completion callback, which runs on the executor thread
+			if thread.thread().id() != thread::current().id() {
+				let _ = thread.join();
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Executor;
+
+	use std::{
+		future::Future,
+		pin::Pin,
+		sync::mpsc,
+		task::{Context, Poll},
+	};
+
+	/// Pending the first time it is polled, waking itself so that it is polled again.
+	struct YieldOnce(bool);
+
+	impl Future for YieldOnce {
+		type Output = usize;
+
+		fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<usize> {
+			if self.0 {
+				return Poll::Ready(7);
+			}
+			self.0 = true;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	}
+
+	#[test]
+	fn spawned_futures_complete() {
+		let executor = Executor::new();
+		let (sender, receiver) = mpsc::channel();
+		executor.spawn(Box::pin(YieldOnce(false)), move |result| {
+			sender.send(result.unwrap().unwrap()).unwrap()
+		});
+		assert_eq!(receiver.recv().unwrap(), 7);
+	}
+
+	#[test]
+	fn panics_are_reported() {
+		let executor = Executor::new();
+		let (sender, receiver) = mpsc::channel();
+		executor.spawn(
+			Box::pin(async { panic!("async task failed") }),
+			move |result: Option<std::thread::Result<()>>| {
+				sender.send(result.unwrap().is_err()).unwrap()
+			},
+		);
+		assert!(receiver.recv().unwrap());
+	}
+
+	#[test]
+	fn pending_futures_are_cancelled() {
+		let executor = Executor::new();
+		let (sender, receiver) = mpsc::channel();
+		executor.spawn(Box::pin(std::future::pending::<()>()), move |result| {
+			sender.send(result.is_none()).unwrap()
+		});
+		executor.cancel_pending();
+		assert!(receiver.try_recv().unwrap());
+		executor.cancel_pending();
+		assert!(receiver.try_recv().is_err());
+	}
+}
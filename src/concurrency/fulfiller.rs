@@ -1,84 +1,163 @@
 use crate::{
 	composition::loaded::loaded_task::LoadedTask,
 	concurrency::{
-		fulfiller_chain::FulfillerChain,
-		possibly_poisoned_mutex::PossiblyPoisonedMutex, ready::Ready,
+		fulfiller_chain::{FulfillerChain, RunContext},
+		fulfiller_state::FulfillerState,
+		possibly_poisoned_mutex::PossiblyPoisonedMutex,
+		ready::Ready,
+	},
+	errors::{
+		custard_error::CustardError,
+		run_errors::custard_task_panic_error::CustardTaskPanicError,
 	},
-	errors::run_errors::custard_task_panic_error::CustardTaskPanicError,
 	identify::task_name::FullTaskName,
 	instance_control_flow::InstanceControlFlow,
-	user_types::task_control_flow::task_control_flow::{
-		TaskControlFlow, TaskHandlerState,
+	recording::cycle_hook::BoundCycleHook,
+	user_types::{
+		task::{TaskClosure, TaskFuture},
+		task_control_flow::task_control_flow::{
+			TaskControlFlow, TaskHandlerState,
+		},
 	},
+	utils::{error_report::report, panic_capture},
 };
 
 use log::{error, info, warn};
-use threadpool::ThreadPool;
 
 use std::{
-	cell::RefCell,
-	panic::{self, AssertUnwindSafe},
+	any::Any,
+	collections::BTreeSet,
+	panic::AssertUnwindSafe,
 	rc::Rc,
-	sync::{Arc, Barrier, BarrierWaitResult, Mutex, Weak},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		mpsc::{self, Receiver, Sender},
+		Arc, Barrier, Mutex, OnceLock, Weak,
+	},
+	thread::{self, ThreadId},
 };
 
+enum MainThreadMessage {
+	Run(Box<dyn FnOnce() + Send>),
+	/// Sent once every fulfiller has ceased. `rendezvous` is false if the last fulfiller ceased on the main thread itself, in which case nobody waits on the barrier.
+	Quit {
+		rendezvous: bool,
+	},
+}
+
 #[derive(Debug)]
 pub struct Quit {
 	///fulfillers that dont have errors and can be rerun in a reload
-	nominal_count: Mutex<usize>,
-	active_count: Mutex<usize>,
+	nominal_count: AtomicUsize,
+	active_count: AtomicUsize,
 	barrier: Barrier,
+	main_thread: Mutex<Option<ThreadId>>,
+	main_thread_sender: Mutex<Sender<MainThreadMessage>>,
+	main_thread_receiver: Mutex<Receiver<MainThreadMessage>>,
 }
 
 impl Quit {
 	pub fn new(active_count: usize) -> Self {
+		let (sender, receiver) = mpsc::channel();
 		Self {
-			nominal_count: Mutex::new(active_count),
-			active_count: Mutex::new(active_count),
+			nominal_count: AtomicUsize::new(active_count),
+			active_count: AtomicUsize::new(active_count),
 			barrier: Barrier::new(2),
+			main_thread: Mutex::new(None),
+			main_thread_sender: Mutex::new(sender),
+			main_thread_receiver: Mutex::new(receiver),
 		}
 	}
 
 	pub(crate) fn begin_fulfillers(&self, num_to_add: isize) {
-		let mut active_g = self.active_count.lock().unwrap();
-		*active_g = ((*active_g as isize) + num_to_add) as usize;
+		//wraps, so adding a negative count subtracts
+		self.active_count
+			.fetch_add(num_to_add as usize, Ordering::AcqRel);
 	}
 
-	pub(crate) fn cease_fulfiller(&self, fulfiller: &Fulfiller) {
-		*fulfiller.cease.lock().unwrap() = true;
+	/// Change the number of fulfillers, both nominal and active, after task instances were added or removed.
+	pub(crate) fn resize_fulfillers(&self, num_to_add: isize) {
+		self.nominal_count
+			.fetch_add(num_to_add as usize, Ordering::AcqRel);
+		self.begin_fulfillers(num_to_add);
+	}
 
-		let mut active_count = self.active_count.lock().unwrap();
+	pub(crate) fn cease_fulfiller(&self, fulfiller: &Fulfiller) {
+		if !fulfiller.state.cease() {
+			return; //already counted
+		}
 
-		*active_count -= 1;
-		info!("New count of active fulfillers: {}", *active_count);
-		if *active_count == 0 {
-			std::mem::drop(active_count);
+		let active_count = self.active_count.fetch_sub(1, Ordering::AcqRel) - 1;
+		info!("New count of active fulfillers: {}", active_count);
+		if active_count == 0 {
 			info!("Waiting for main thread to quit.");
-			self.barrier.wait(); //return to this call in CustardInstance. Make sure this doesnt get called twice and set off a deadlock
+			let rendezvous = !self.is_main_thread();
+			self.main_thread_sender
+				.lock()
+				.unwrap()
+				.send(MainThreadMessage::Quit { rendezvous })
+				.unwrap();
+			if rendezvous {
+				self.barrier.wait(); //return to this call in CustardInstance. Make sure this doesnt get called twice and set off a deadlock
+			}
 		}
 	}
 
-	pub(crate) fn main_thread_wait(&self) -> BarrierWaitResult {
-		self.barrier.wait()
+	fn is_main_thread(&self) -> bool {
+		*self.main_thread.lock().unwrap() == Some(thread::current().id())
+	}
+
+	/// Hand work on a main-thread chain to the thread blocked in [main_thread_wait](Self::main_thread_wait).
+	pub(crate) fn run_on_main_thread(&self, job: Box<dyn FnOnce() + Send>) {
+		self.main_thread_sender
+			.lock()
+			.unwrap()
+			.send(MainThreadMessage::Run(job))
+			.unwrap();
+	}
+
+	/// Block the calling thread until every fulfiller has ceased, running main-thread chains as they become ready.
+	pub(crate) fn main_thread_wait(&self) {
+		*self.main_thread.lock().unwrap() = Some(thread::current().id());
+		let receiver = self.main_thread_receiver.lock().unwrap();
+		loop {
+			match receiver.recv().unwrap() {
+				MainThreadMessage::Run(job) => job(),
+				MainThreadMessage::Quit { rendezvous } => {
+					if rendezvous {
+						self.barrier.wait();
+					}
+					return;
+				}
+			}
+		}
 	}
 
 	pub(crate) unsafe fn reset(&self) -> usize {
 		info!("Resetting active/nominal counts and barrier for reload");
-		let nominal_count = *self.nominal_count.lock().unwrap();
-		*self.active_count.lock().unwrap() = nominal_count;
+		let nominal_count = self.nominal_count.load(Ordering::Acquire);
+		self.active_count.store(nominal_count, Ordering::Release);
 		*((&self.barrier as *const _) as *mut Barrier) = Barrier::new(2);
+		//chains that became ready after the last fulfiller ceased belong to the previous run
+		while self.main_thread_receiver.lock().unwrap().try_recv().is_ok() {}
 		nominal_count
 	}
 }
 
 #[derive(Debug)]
 pub struct Fulfiller {
-	pub cease: Mutex<bool>,
-	pub error: Mutex<bool>,
+	/// Whether the fulfiller is running, ceased or errored, and whether a parent returning `TaskControlFlow::SkipDownstream` marked it to be skipped when it next runs.
+	pub state: FulfillerState,
+	/// Set once when the composition is connected, after every fulfiller exists.
+	pub children: OnceLock<Vec<Weak<Fulfiller>>>,
 	pub children_chains: Vec<Weak<FulfillerChain>>,
 	pub done: Ready,
 	pub prerequisites: Vec<Weak<Fulfiller>>,
 	pub task: Option<LoadedTask>,
+	/// Run before the task each time this fulfiller begins a cycle.
+	pub cycle_hooks: Vec<BoundCycleHook>,
+	/// Set while a hook stands in for this task, for example a replay of the datachunks it writes. The task is released without running its closure.
+	pub disabled: bool,
 }
 
 impl Fulfiller {
@@ -96,6 +175,38 @@ impl Fulfiller {
 		true
 	}
 
+	/// Whether every parent skipped its task this cycle, in which case this task is skipped too. Entrypoints begin a cycle, so they never are.
+	fn prerequisites_skipped(&self) -> bool {
+		if self.done.is_entrypoint() || self.prerequisites.is_empty() {
+			return false;
+		}
+		self.prerequisites.iter().all(|prerequisite| {
+			match prerequisite.upgrade() {
+				Some(v) => v.state.was_skipped(),
+				None => false, //program is exiting
+			}
+		})
+	}
+
+	/// Mark children as skipped for the current cycle. Children that are entrypoints begin the next cycle, so they are left alone.
+	fn skip_children(&self, targets: &Option<Arc<BTreeSet<FullTaskName>>>) {
+		for child in self.children.get().into_iter().flatten() {
+			let child = match child.upgrade() {
+				Some(v) => v,
+				None => continue, //program is exiting
+			};
+			if child.done.is_entrypoint() {
+				continue;
+			}
+			if let Some(targets) = targets {
+				if !targets.contains(&child.task.as_ref().unwrap().name) {
+					continue;
+				}
+			}
+			child.state.skip();
+		}
+	}
+
 	fn notify_tasks_of_control_flow_change(
 		current_task_name: &FullTaskName,
 		task_result: &TaskControlFlow,
@@ -110,7 +221,7 @@ impl Fulfiller {
 				}
 				let fulfiller = upgraded.as_ref().unwrap();
 
-				if *fulfiller.cease.lock().unwrap() {
+				if fulfiller.state.is_ceased() {
 					continue;
 				}
 
@@ -132,21 +243,24 @@ impl Fulfiller {
 				};
 
 				let cease = match task_result {
-					TaskControlFlow::Continue => unreachable!(),
+					TaskControlFlow::Continue
+					| TaskControlFlow::SkipDownstream(_) => unreachable!(),
 					TaskControlFlow::Err(error) => {
-						match error.downcast_ref::<CustardTaskPanicError>() {
-							Some(_) => {
+						match error.downcast_ref::<CustardError>() {
+							Some(CustardError::TaskPanic(_)) => {
 								warn!(
 									"Exiting because a task panicked: {:?}",
 									fulfiller.task.as_ref().unwrap().name
 								);
 								true
 							}
-							None => error_or_stop(),
+							_ => error_or_stop(),
 						}
 					}
 					TaskControlFlow::StopThis => error_or_stop(),
-					TaskControlFlow::FullReload
+					TaskControlFlow::Checkpoint(_)
+					| TaskControlFlow::EditTasks(_)
+					| TaskControlFlow::FullReload
 					| TaskControlFlow::PartialReload(_)
 					| TaskControlFlow::StopAll => true,
 				};
@@ -161,102 +275,431 @@ impl Fulfiller {
 		}
 	}
 
-	/// Runs a task. Returns true if it panicked, so the remainder of the chain can occur in a different thread. Even in the case of an error, run_task will call `Ready::release()` to allow other tasks to interpret the error.
+	/// Runs a task. Returns false if the task is async and its future is still pending, in which case the remainder of `chain`, from `resume_at`, is run once the future resolves. Even in the case of an error, run_task will call `Ready::release()` to allow other tasks to interpret the error.
 	pub(crate) fn run_task(
-		&self,
-		pool: ThreadPool,
-		quit: Arc<Quit>,
-		all_chains: Arc<Vec<Arc<FulfillerChain>>>,
-		instance_control_flow: Arc<PossiblyPoisonedMutex<InstanceControlFlow>>,
-	) {
+		self: &Arc<Self>,
+		chain: &Arc<FulfillerChain>,
+		resume_at: usize,
+		context: &RunContext,
+	) -> bool {
 		if !self.prerequisites_complete() {
-			return;
+			return true;
 		}
 
-		let cease = { *self.cease.lock().unwrap() };
-
-		if !cease {
-			let closure_result: TaskControlFlow;
-			let safe_closure_result =
-				AssertUnwindSafe(RefCell::new(Some(TaskControlFlow::Continue)));
-			let safe_self = AssertUnwindSafe(self);
-			let panic_result = panic::catch_unwind(|| {
-				let user_task = &safe_self.task;
-				*safe_closure_result.borrow_mut() = Some((user_task
-					.as_ref()
-					.unwrap()
-					.closure
-					.as_ref()
-					.unwrap()
-					.lock()
-					.unwrap())(
-					user_task.as_ref().unwrap().user_data.clone(),
-				));
-			});
+		let (cease, skip) = match self.state.begin_run() {
+			Some(v) => (v.ceased, v.skip || self.prerequisites_skipped()),
+			None => return true, //already dispatched by another prerequisite
+		};
+		self.state.set_skipped(skip && !cease);
 
-			match panic_result {
-				Err(e) => {
-					let panic_error = CustardTaskPanicError {
-						offending_task: self
-							.task
-							.as_ref()
-							.unwrap()
-							.name
-							.clone(),
-						error: e,
-					};
-
-					closure_result = TaskControlFlow::Err(Rc::new(panic_error));
-					*instance_control_flow.lock() =
-						InstanceControlFlow::RecreateThreadpool;
+		let closure_result = if !cease
+			&& self.done.is_entrypoint()
+			&& context.run_options.reached(self.done.cycles())
+		{
+			info!(
+				"Run limit reached, stopping at entrypoint: {:?}",
+				self.task.as_ref().unwrap().name
+			);
+			Some(TaskControlFlow::StopAll)
+		} else if skip && !cease {
+			info!(
+				"Skipping task for this cycle: {:?}",
+				self.task.as_ref().unwrap().name
+			);
+			None
+		} else if !cease {
+			for hook in &self.cycle_hooks {
+				hook.run();
+			}
+			if self.disabled {
+				self.complete_task(None, context);
+				return true;
+			}
+			let user_task = self.task.as_ref().unwrap();
+			let user_data = user_task.user_data.clone();
+			match user_task.closure.as_ref().unwrap() {
+				TaskClosure::Sync(closure) => {
+					match panic_capture::catch_unwind(AssertUnwindSafe(|| {
+						(closure.lock().unwrap())(user_data)
+					})) {
+						Ok(v) => Some(v),
+						Err(e) => Some(
+							self.panicked(e, &context.instance_control_flow),
+						),
+					}
 				}
-				Ok(_) => {
-					closure_result = safe_closure_result.take().unwrap();
+				TaskClosure::Async(closure) => {
+					match panic_capture::catch_unwind(AssertUnwindSafe(|| {
+						(closure.lock().unwrap())(user_data)
+					})) {
+						Ok(future) => {
+							self.spawn_future(
+								future,
+								chain.clone(),
+								resume_at,
+								context.clone(),
+							);
+							return false;
+						}
+						Err(e) => Some(
+							self.panicked(e, &context.instance_control_flow),
+						),
+					}
 				}
 			}
+		} else {
+			None
+		};
+
+		self.complete_task(closure_result, context);
+		true
+	}
+
+	/// Turn a panic payload into a task error. Must be called on the thread that panicked, so the panic hook's report can be attached.
+	fn panicked(
+		&self,
+		payload: Box<dyn Any + Send>,
+		instance_control_flow: &PossiblyPoisonedMutex<InstanceControlFlow>,
+	) -> TaskControlFlow {
+		let task = self.task.as_ref().unwrap();
+		let panic_error = CustardTaskPanicError::new(
+			task.name.clone(),
+			task.library_path.clone(),
+			payload,
+		);
+		instance_control_flow
+			.lock()
+			.merge(InstanceControlFlow::RecreateThreadpool);
+		TaskControlFlow::Err(Rc::new(CustardError::from(panic_error)))
+	}
 
+	/// Drive the future of an async task on the executor. Once it resolves, the task is completed on the executor thread, and the rest of its chain is run on the main thread or the scheduler as the chain requires. If the run ends first, the future is dropped and the task is released without a result or dispatching anything, so that nothing it would have started leaks into the next run.
+	fn spawn_future(
+		self: &Arc<Self>,
+		future: TaskFuture,
+		chain: Arc<FulfillerChain>,
+		resume_at: usize,
+		context: RunContext,
+	) {
+		let upgraded = match context.executor.upgrade() {
+			Some(v) => v,
+			None => return, //program is exiting
+		};
+		let this = self.clone();
+		upgraded.spawn(future, move |result| {
+			let closure_result = match result {
+				Some(Ok(v)) => v,
+				Some(Err(e)) => {
+					this.panicked(e, &context.instance_control_flow)
+				}
+				None => {
+					info!(
+						"Async task dropped at the end of the run: {:?}",
+						this.task.as_ref().unwrap().name
+					);
+					this.state.finish_run();
+					this.done.release();
+					return;
+				}
+			};
+			//completing only dispatches other chains, so it is cheap enough for the executor thread
+			this.complete_task(Some(closure_result), &context);
+			let resumed_chain = chain.clone();
+			let resumed_context = context.clone();
+			chain.dispatch(&context, move || {
+				resumed_chain.run(resume_at, &resumed_context);
+			});
+		});
+	}
+
+	/// Act on what the task returned, if it ran, then release it and attempt to run the chains that depend on it.
+	fn complete_task(
+		&self,
+		closure_result: Option<TaskControlFlow>,
+		context: &RunContext,
+	) {
+		let instance_control_flow = &context.instance_control_flow;
+		if let Some(closure_result) = closure_result {
 			match &closure_result {
 				TaskControlFlow::Continue => {}
+				TaskControlFlow::SkipDownstream(targets) => {
+					self.skip_children(targets)
+				}
 				_ => {
 					match &closure_result {
-						TaskControlFlow::FullReload => {
-							*instance_control_flow.lock() =
-								InstanceControlFlow::FullReload
+						TaskControlFlow::Checkpoint(path) => {
+							instance_control_flow.lock().merge(
+								InstanceControlFlow::Checkpoint(
+									path.clone(),
+									Box::new(InstanceControlFlow::Continue),
+								),
+							)
+						}
+						TaskControlFlow::EditTasks(edits) => {
+							instance_control_flow.lock().merge(
+								InstanceControlFlow::EditTasks(edits.clone()),
+							)
 						}
+						TaskControlFlow::FullReload => instance_control_flow
+							.lock()
+							.merge(InstanceControlFlow::FullReload),
 						TaskControlFlow::PartialReload(v) => {
-							*instance_control_flow.lock() =
-								InstanceControlFlow::PartialReload(v.clone())
+							instance_control_flow.lock().merge(
+								InstanceControlFlow::PartialReload(v.clone()),
+							)
 						}
 						TaskControlFlow::Err(e) => {
-							*self.error.lock().unwrap() = true;
-							*quit.nominal_count.lock().unwrap() -= 1;
-							error!("Task error: {}", e);
-						}
-						TaskControlFlow::StopAll => {
-							*instance_control_flow.lock() =
-								InstanceControlFlow::Stop
+							self.state.error();
+							context
+								.quit
+								.nominal_count
+								.fetch_sub(1, Ordering::AcqRel);
+							error!("Task error: {}", report(&**e));
 						}
+						TaskControlFlow::StopAll => instance_control_flow
+							.lock()
+							.merge(InstanceControlFlow::Stop),
 						_ => {}
 					}
 
 					Self::notify_tasks_of_control_flow_change(
 						&self.task.as_ref().unwrap().name,
 						&closure_result,
-						&all_chains,
-						quit.clone(),
+						&context.all_chains,
+						context.quit.clone(),
 					);
 				}
 			};
 		}
+		self.state.finish_run();
 		self.done.release();
 
 		for child_chain in &self.children_chains {
-			child_chain.upgrade().unwrap().attempt_to_run(
-				quit.clone(),
-				pool.clone(),
-				all_chains.clone(),
-				instance_control_flow.clone(),
-			);
+			child_chain.upgrade().unwrap().attempt_to_run(context);
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	extern crate test;
+
+	use super::{Fulfiller, Quit};
+
+	use crate::{
+		composition::loaded::{
+			datachunk_getter::DatachunkGetter, loaded_task::LoadedTask,
+		},
+		concurrency::{
+			fulfiller_chain::{FulfillerChain, RunContext},
+			fulfiller_state::FulfillerState,
+			possibly_poisoned_mutex::PossiblyPoisonedMutex,
+			ready::Ready,
+			work_stealing::WorkStealingScheduler,
+		},
+		identify::task_name::FullTaskName,
+		instance_control_flow::InstanceControlFlow,
+		run_options::RunOptions,
+		user_types::{
+			task::{TaskClosure, Taskable},
+			task_control_flow::task_control_flow::{
+				TaskControlFlow, TaskHandlerState,
+			},
+		},
+	};
+
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+	use test::Bencher;
+
+	use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+	#[derive(Debug)]
+	struct ClosureOnly;
+
+	impl Taskable for ClosureOnly {
+		fn run(
+			&mut self,
+			_: FullTaskName,
+			_: Arc<DatachunkGetter>,
+		) -> TaskClosure {
+			unreachable!("the closure is given to the LoadedTask")
+		}
+
+		fn handle_control_flow_update(
+			&mut self,
+			_: &FullTaskName,
+			_: &FullTaskName,
+			_: &TaskControlFlow,
+		) -> TaskHandlerState {
+			TaskHandlerState::Continue
+		}
+	}
+
+	fn fulfiller(
+		entrypoint: bool,
+		prerequisites: &[&Arc<Fulfiller>],
+	) -> Arc<Fulfiller> {
+		Arc::new(Fulfiller {
+			state: FulfillerState::new(),
+			children: OnceLock::new(),
+			children_chains: vec![],
+			done: Ready::new(entrypoint),
+			prerequisites: prerequisites
+				.iter()
+				.map(|v| Arc::downgrade(v))
+				.collect(),
+			task: None,
+			cycle_hooks: vec![],
+			disabled: false,
+		})
+	}
+
+	#[test]
+	fn skips_pass_down_only_when_every_parent_skipped() {
+		//a begins the cycle, b and c follow it, d waits on both and e on b
+		let a = fulfiller(true, &[]);
+		let b = fulfiller(false, &[&a]);
+		let c = fulfiller(false, &[&a]);
+		let d = fulfiller(false, &[&b, &c]);
+		let e = fulfiller(false, &[&b]);
+
+		b.state.set_skipped(true);
+		assert!(!d.prerequisites_skipped());
+		assert!(e.prerequisites_skipped());
+
+		c.state.set_skipped(true);
+		assert!(d.prerequisites_skipped());
+
+		a.state.set_skipped(true);
+		let a_again = fulfiller(true, &[&a]);
+		assert!(!a_again.prerequisites_skipped());
+	}
+
+	#[test]
+	fn skip_marks_children_other_than_entrypoints() {
+		let a = fulfiller(true, &[]);
+		let b = fulfiller(false, &[&a]);
+		a.children.set(vec![Arc::downgrade(&b)]).unwrap();
+		b.children.set(vec![Arc::downgrade(&a)]).unwrap();
+
+		a.skip_children(&None);
+		b.skip_children(&None);
+		assert!(b.state.begin_run().unwrap().skip);
+		assert!(!a.state.begin_run().unwrap().skip);
+	}
+
+	fn name(task_name: usize) -> FullTaskName {
+		FullTaskName::new("c".to_owned(), task_name.to_string())
+	}
+
+	/// The parents of each task in a cycle of `layers` layers of `width` tasks, each with up to two parents in the layer before. Task 0 is the first layer and the only entrypoint, and waits on the whole last layer.
+	fn generate(layers: usize, width: usize) -> Vec<Vec<usize>> {
+		let mut rng = StdRng::seed_from_u64(0);
+		let layer = |i: usize| match i {
+			0 => 0..1,
+			_ => (i - 1) * width + 1..i * width + 1,
+		};
+		let mut parents = vec![layer(layers - 1).collect()];
+		for i in 1..layers {
+			for _ in 0..width {
+				let mut task_parents: Vec<usize> =
+					(0..2).map(|_| rng.gen_range(layer(i - 1))).collect();
+				task_parents.sort();
+				task_parents.dedup();
+				parents.push(task_parents);
+			}
+		}
+		parents
+	}
+
+	/// Build the fulfiller of task `chains.len()` onwards, each in a chain of its own on the main thread. Each chain is created, with a weak reference to its fulfiller, before the fulfillers after it, so that every fulfiller can refer to its parents and to the chains of its children.
+	fn connect(
+		parents: &[Vec<usize>],
+		children: &[Vec<usize>],
+		task: &dyn Fn(usize) -> LoadedTask,
+		chains: &mut Vec<Arc<FulfillerChain>>,
+		fulfillers: &mut Vec<Arc<Fulfiller>>,
+	) {
+		let i = chains.len();
+		if i == parents.len() {
+			return;
+		}
+		let fulfiller = Arc::new_cyclic(|weak| {
+			chains.push(Arc::new(FulfillerChain {
+				first_name: name(i),
+				chain: vec![weak.clone()],
+				main_thread: true,
+			}));
+			connect(parents, children, task, chains, fulfillers);
+			let weaks = |tasks: &[usize]| {
+				tasks.iter().map(|v| chains[*v].chain[0].clone()).collect()
+			};
+			Fulfiller {
+				state: FulfillerState::new(),
+				children: OnceLock::from(weaks(&children[i])),
+				children_chains: children[i]
+					.iter()
+					.map(|v| Arc::downgrade(&chains[*v]))
+					.collect(),
+				done: Ready::new(i == 0),
+				prerequisites: weaks(&parents[i]),
+				task: Some(task(i)),
+				cycle_hooks: vec![],
+				disabled: false,
+			}
+		});
+		fulfillers.push(fulfiller);
+	}
+
+	/// Ten cycles of 257 tasks per iteration, dispatched through `run_task` as a run of an instance is, until the entrypoint returns `StopAll` and every fulfiller ceases. The chains run on the main thread, so the run stays on the bench thread, and what is dispatched after the last fulfiller ceases is discarded by `Quit::reset` as it is between runs.
+	#[bench]
+	fn dispatch_10_cycles_of_257_tasks(b: &mut Bencher) {
+		let parents = generate(17, 16);
+		let mut children = vec![vec![]; parents.len()];
+		for (i, task_parents) in parents.iter().enumerate() {
+			for parent in task_parents {
+				children[*parent].push(i);
+			}
+		}
+		let task = |i: usize| {
+			let mut cycles = 0;
+			let closure = move |_| {
+				cycles += 1;
+				match i == 0 && cycles % 10 == 0 {
+					true => TaskControlFlow::StopAll,
+					false => TaskControlFlow::Continue,
+				}
+			};
+			LoadedTask::with_closure(
+				name(i),
+				Arc::new(PossiblyPoisonedMutex::new(Mutex::new(ClosureOnly))),
+				TaskClosure::Sync(Box::new(Mutex::new(closure))),
+			)
+		};
+		let mut chains = vec![];
+		let mut fulfillers = vec![];
+		connect(&parents, &children, &task, &mut chains, &mut fulfillers);
+
+		let quit = Arc::new(Quit::new(parents.len()));
+		let context = RunContext {
+			quit: quit.clone(),
+			scheduler: Arc::new(WorkStealingScheduler::new(1)),
+			executor: Weak::new(),
+			all_chains: Arc::new(chains),
+			instance_control_flow: Arc::new(PossiblyPoisonedMutex::new(
+				Mutex::new(InstanceControlFlow::Continue),
+			)),
+			run_options: RunOptions::default(),
+		};
+		b.iter(|| {
+			for chain in &*context.all_chains {
+				chain.clone().attempt_to_run(&context);
+			}
+			quit.main_thread_wait();
+			unsafe { quit.reset() };
+			for fulfiller in &fulfillers {
+				fulfiller.state.resume();
+			}
+		});
+	}
+}
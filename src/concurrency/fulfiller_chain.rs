@@ -1,53 +1,68 @@
 use crate::{
 	concurrency::{
+		executor::Executor,
 		fulfiller::{Fulfiller, Quit},
 		possibly_poisoned_mutex::PossiblyPoisonedMutex,
+		scheduler::Scheduler,
 	},
 	identify::task_name::FullTaskName,
 	instance_control_flow::InstanceControlFlow,
+	run_options::RunOptions,
 };
 
 use log::warn;
-use threadpool::ThreadPool;
 
 use std::sync::{Arc, Weak};
 
+/// What the chains of a run share, to dispatch one another and report how the instance should continue.
+#[derive(Clone)]
+pub(crate) struct RunContext {
+	pub quit: Arc<Quit>,
+	pub scheduler: Arc<dyn Scheduler>,
+	pub executor: Weak<Executor>,
+	pub all_chains: Arc<Vec<Arc<FulfillerChain>>>,
+	pub instance_control_flow: Arc<PossiblyPoisonedMutex<InstanceControlFlow>>,
+	/// Limits for the current run, with cycles run by earlier compositions already taken off. Read by every entrypoint as it begins a cycle.
+	pub run_options: RunOptions,
+}
+
 #[derive(Debug)]
 pub struct FulfillerChain {
 	pub first_name: FullTaskName,
 	pub chain: Vec<Weak<Fulfiller>>,
+	/// Whether the chain runs on the thread that called `CustardInstance::run` rather than on the scheduler. Chains never mix main-thread and scheduler tasks.
+	pub main_thread: bool,
 }
 
 impl FulfillerChain {
-	/// The start at parameter is intended for use with moving threads, such as in the case of a panic.
-	pub(super) fn run(
-		self: &Arc<Self>,
-		quit: Arc<Quit>,
-		pool: ThreadPool,
-		all_chains: Arc<Vec<Arc<Self>>>,
-		instance_control_flow: Arc<PossiblyPoisonedMutex<InstanceControlFlow>>,
-	) {
-		for fulfiller_i in 0..self.chain.len() {
+	/// The start at parameter is intended for use with moving threads, such as when an async task resolves partway through the chain.
+	pub(super) fn run(self: &Arc<Self>, start_at: usize, context: &RunContext) {
+		for fulfiller_i in start_at..self.chain.len() {
 			let fulfiller = match self.chain[fulfiller_i].upgrade() {
 				Some(v) => v,
 				None => return, //program is exiting
 			};
-			fulfiller.run_task(
-				pool.clone(),
-				quit.clone(),
-				all_chains.clone(),
-				instance_control_flow.clone(),
-			)
+			let finished = fulfiller.run_task(self, fulfiller_i + 1, context);
+			if !finished {
+				return; //the rest of the chain is run once the async task resolves
+			}
 		}
 	}
 
-	pub(crate) fn attempt_to_run(
-		self: Arc<Self>,
-		quit: Arc<Quit>,
-		pool: ThreadPool,
-		all_chains: Arc<Vec<Arc<Self>>>,
-		instance_control_flow: Arc<PossiblyPoisonedMutex<InstanceControlFlow>>,
+	/// Run `job` on the main thread or on the scheduler, whichever this chain belongs to.
+	pub(super) fn dispatch(
+		&self,
+		context: &RunContext,
+		job: impl FnOnce() + Send + 'static,
 	) {
+		if self.main_thread {
+			context.quit.run_on_main_thread(Box::new(job));
+		} else {
+			context.scheduler.execute(Box::new(job));
+		}
+	}
+
+	pub(crate) fn attempt_to_run(self: Arc<Self>, context: &RunContext) {
 		let first_fulfiller = {
 			match self.chain[0].upgrade() {
 				Some(v) => v,
@@ -59,14 +74,10 @@ impl FulfillerChain {
 		};
 
 		if first_fulfiller.prerequisites_complete() {
-			let pool_inner = pool.clone();
-			pool.execute(move || {
-				self.clone().run(
-					quit.clone(),
-					pool_inner,
-					all_chains,
-					instance_control_flow,
-				);
+			let chain = self.clone();
+			let inner_context = context.clone();
+			self.dispatch(context, move || {
+				chain.run(0, &inner_context);
 			});
 		}
 	}
@@ -0,0 +1,160 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const RUNNING: u8 = 1;
+const CEASED: u8 = 1 << 1;
+const ERRORED: u8 = 1 << 2;
+const SKIP: u8 = 1 << 3;
+const SKIPPED: u8 = 1 << 4;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FulfillerLifecycle {
+	Active,
+	Running,
+	/// Will not run its task again until resumed for the next run of the instance.
+	Ceased,
+	/// Ceased because its task returned an error, and is not resumed.
+	Errored,
+}
+
+/// What a fulfiller should do with the task it is about to run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RunStart {
+	pub ceased: bool,
+	pub skip: bool,
+}
+
+/// The lifecycle of a [Fulfiller](crate::concurrency::fulfiller::Fulfiller), together with its one-cycle skip mark and whether its last run was skipped, kept in a single atomic so that no lock is taken while dispatching.
+///
+/// |from|to|by|
+/// |----|----|----|
+/// |active|running|[begin_run](Self::begin_run)|
+/// |running|active|[finish_run](Self::finish_run)|
+/// |active or running|ceased|[cease](Self::cease)|
+/// |any|errored|[error](Self::error)|
+/// |ceased|active|[resume](Self::resume)|
+#[derive(Debug, Default)]
+pub struct FulfillerState {
+	bits: AtomicU8,
+}
+
+impl FulfillerState {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn lifecycle(&self) -> FulfillerLifecycle {
+		let bits = self.bits.load(Ordering::Acquire);
+		if bits & ERRORED != 0 {
+			FulfillerLifecycle::Errored
+		} else if bits & CEASED != 0 {
+			FulfillerLifecycle::Ceased
+		} else if bits & RUNNING != 0 {
+			FulfillerLifecycle::Running
+		} else {
+			FulfillerLifecycle::Active
+		}
+	}
+
+	pub fn is_ceased(&self) -> bool {
+		self.bits.load(Ordering::Acquire) & CEASED != 0
+	}
+
+	/// Mark the fulfiller as running and take its skip mark. Returns `None` if it is already running, which happens when two of its prerequisites complete at once and both dispatch its chain.
+	pub(crate) fn begin_run(&self) -> Option<RunStart> {
+		let previous = self
+			.bits
+			.fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+				if bits & RUNNING != 0 {
+					return None;
+				}
+				Some((bits | RUNNING) & !SKIP)
+			})
+			.ok()?;
+		Some(RunStart {
+			ceased: previous & CEASED != 0,
+			skip: previous & SKIP != 0,
+		})
+	}
+
+	pub(crate) fn finish_run(&self) {
+		self.bits.fetch_and(!RUNNING, Ordering::Release);
+	}
+
+	/// Returns whether this call ceased the fulfiller, so that it is only counted once.
+	pub(crate) fn cease(&self) -> bool {
+		self.bits.fetch_or(CEASED, Ordering::AcqRel) & CEASED == 0
+	}
+
+	/// Mark the fulfiller as errored. It still has to be ceased.
+	pub(crate) fn error(&self) {
+		self.bits.fetch_or(ERRORED, Ordering::AcqRel);
+	}
+
+	/// Let a ceased fulfiller run again, unless it errored. Returns whether it was resumed.
+	pub(crate) fn resume(&self) -> bool {
+		self.bits
+			.fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+				if bits & ERRORED != 0 {
+					return None;
+				}
+				Some(bits & !CEASED)
+			})
+			.is_ok()
+	}
+
+	/// Skip the task the next time the fulfiller runs.
+	pub(crate) fn skip(&self) {
+		self.bits.fetch_or(SKIP, Ordering::AcqRel);
+	}
+
+	/// Record whether the run in progress skipped its task, for children deciding whether to skip theirs.
+	pub(crate) fn set_skipped(&self, skipped: bool) {
+		if skipped {
+			self.bits.fetch_or(SKIPPED, Ordering::AcqRel);
+		} else {
+			self.bits.fetch_and(!SKIPPED, Ordering::AcqRel);
+		}
+	}
+
+	pub fn was_skipped(&self) -> bool {
+		self.bits.load(Ordering::Acquire) & SKIPPED != 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{FulfillerLifecycle, FulfillerState, RunStart};
+
+	#[test]
+	fn lifecycle_transitions() {
+		let state = FulfillerState::new();
+		state.skip();
+		assert_eq!(
+			state.begin_run(),
+			Some(RunStart {
+				ceased: false,
+				skip: true
+			})
+		);
+		assert_eq!(state.lifecycle(), FulfillerLifecycle::Running);
+		assert_eq!(state.begin_run(), None);
+		assert!(state.cease());
+		assert!(!state.cease());
+		state.finish_run();
+		assert_eq!(state.lifecycle(), FulfillerLifecycle::Ceased);
+		assert_eq!(
+			state.begin_run(),
+			Some(RunStart {
+				ceased: true,
+				skip: false
+			})
+		);
+		state.finish_run();
+		assert!(state.resume());
+		assert_eq!(state.lifecycle(), FulfillerLifecycle::Active);
+		state.error();
+		state.cease();
+		assert!(!state.resume());
+		assert_eq!(state.lifecycle(), FulfillerLifecycle::Errored);
+	}
+}
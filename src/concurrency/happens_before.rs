@@ -0,0 +1,349 @@
+use crate::{
+	composition::unloaded::unloaded_composition::UnloadedComposition,
+	identify::task_name::FullTaskName,
+};
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// Which tasks of a composition are ordered within a cycle, computed once so that each pair can then be looked up rather than searched for.
+///
+/// A task happens before another if following parents back from the other reaches it. Entrypoints begin a cycle, so the parents of an entrypoint belong to the cycle before and are not followed. Two tasks are synchronized if one happens before the other, and each also reaches the other when every parent is followed, entrypoints' included, so that the earlier task cannot begin its next cycle before the later one completes. Otherwise they may run at the same time.
+#[derive(Debug)]
+pub struct HappensBefore {
+	indices: BTreeMap<FullTaskName, usize>,
+	/// For each task, a bitset of the tasks that happen before it.
+	ancestors: Vec<Vec<u64>>,
+	/// For each task, its strongly connected component when every parent is followed, entrypoints' included.
+	components: Vec<usize>,
+}
+
+impl HappensBefore {
+	pub fn new(composition: &UnloadedComposition) -> Self {
+		let mut indices = BTreeMap::new();
+		let mut tasks = vec![];
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				indices.insert(
+					FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					},
+					tasks.len(),
+				);
+				tasks.push(task_contents);
+			}
+		}
+
+		//every parent, by index
+		let all_parents: Vec<Vec<usize>> = tasks
+			.iter()
+			.enumerate()
+			.map(|(i, task)| {
+				task.parents
+					.iter()
+					.filter_map(|v| indices.get(v).copied())
+					.filter(|v| *v != i)
+					.collect()
+			})
+			.collect();
+		let components = Self::components(&all_parents);
+
+		//the parents followed within a cycle
+		let parents: Vec<Vec<usize>> = all_parents
+			.into_iter()
+			.zip(tasks.iter())
+			.map(|(v, task)| if task.entrypoint { vec![] } else { v })
+			.collect();
+		let mut children = vec![vec![]; tasks.len()];
+		for (i, task_parents) in parents.iter().enumerate() {
+			for parent in task_parents {
+				children[*parent].push(i);
+			}
+		}
+
+		let words = tasks.len().div_ceil(64);
+		let mut ancestors = vec![vec![0u64; words]; tasks.len()];
+
+		//a task's ancestors are its parents and theirs, so visit parents first
+		let mut waiting: Vec<usize> = parents.iter().map(|v| v.len()).collect();
+		let mut ready: VecDeque<usize> =
+			(0..tasks.len()).filter(|i| waiting[*i] == 0).collect();
+		let mut visited = 0;
+		while let Some(i) = ready.pop_front() {
+			visited += 1;
+			Self::gather(&mut ancestors, &parents[i], i);
+			for child in &children[i] {
+				waiting[*child] -= 1;
+				if waiting[*child] == 0 {
+					ready.push_back(*child);
+				}
+			}
+		}
+
+		//tasks on a loop with no entrypoint never run, which the check reports, but the relation should still be complete for them
+		if visited < tasks.len() {
+			loop {
+				let mut changed = false;
+				for (i, task_parents) in parents.iter().enumerate() {
+					changed |= Self::gather(&mut ancestors, task_parents, i);
+				}
+				if !changed {
+					break;
+				}
+			}
+		}
+
+		Self {
+			indices,
+			ancestors,
+			components,
+		}
+	}
+
+	/// Number the strongly connected components of the graph of `parents`, with Tarjan's algorithm. Iterative, as the loops of a composition can be long.
+	fn components(parents: &[Vec<usize>]) -> Vec<usize> {
+		const UNVISITED: usize = usize::MAX;
+		let mut index = vec![UNVISITED; parents.len()];
+		let mut low = vec![0; parents.len()];
+		let mut on_stack = vec![false; parents.len()];
+		let mut stack = vec![];
+		let mut components = vec![UNVISITED; parents.len()];
+		let mut next_index = 0;
+		let mut next_component = 0;
+
+		for root in 0..parents.len() {
+			if index[root] != UNVISITED {
+				continue;
+			}
+			index[root] = next_index;
+			low[root] = next_index;
+			next_index += 1;
+			stack.push(root);
+			on_stack[root] = true;
+			//each task being visited, and how many of its parents it has gone through
+			let mut calls = vec![(root, 0)];
+			while let Some((i, edge)) = calls.pop() {
+				if let Some(parent) = parents[i].get(edge).copied() {
+					calls.push((i, edge + 1));
+					if index[parent] == UNVISITED {
+						index[parent] = next_index;
+						low[parent] = next_index;
+						next_index += 1;
+						stack.push(parent);
+						on_stack[parent] = true;
+						calls.push((parent, 0));
+					} else if on_stack[parent] {
+						low[i] = low[i].min(index[parent]);
+					}
+					continue;
+				}
+				if low[i] == index[i] {
+					loop {
+						let member = stack.pop().unwrap();
+						on_stack[member] = false;
+						components[member] = next_component;
+						if member == i {
+							break;
+						}
+					}
+					next_component += 1;
+				}
+				if let Some((caller, _)) = calls.last() {
+					low[*caller] = low[*caller].min(low[i]);
+				}
+			}
+		}
+		components
+	}
+
+	/// Add the parents of task `i`, and their ancestors, to the ancestors of `i`. Returns whether anything was added.
+	fn gather(ancestors: &mut [Vec<u64>], parents: &[usize], i: usize) -> bool {
+		let mut gathered = ancestors[i].clone();
+		for parent in parents {
+			for (word, parent_word) in
+				gathered.iter_mut().zip(ancestors[*parent].iter())
+			{
+				*word |= parent_word;
+			}
+			gathered[parent / 64] |= 1 << (parent % 64);
+		}
+		let changed = gathered != ancestors[i];
+		ancestors[i] = gathered;
+		changed
+	}
+
+	/// The index of a task, for the queries by index, which skip looking up names.
+	pub fn index_of(&self, task_name: &FullTaskName) -> Option<usize> {
+		self.indices.get(task_name).copied()
+	}
+
+	/// Whether `task_name` always completes before `other_task_name` begins, in the same cycle. Tasks not in the composition happen before nothing.
+	pub fn happens_before(
+		&self,
+		task_name: &FullTaskName,
+		other_task_name: &FullTaskName,
+	) -> bool {
+		match (self.index_of(task_name), self.index_of(other_task_name)) {
+			(Some(i), Some(other_i)) => self.happens_before_at(i, other_i),
+			_ => false,
+		}
+	}
+
+	pub fn happens_before_at(&self, i: usize, other_i: usize) -> bool {
+		self.ancestors[other_i][i / 64] & (1 << (i % 64)) != 0
+	}
+
+	/// Whether the two tasks may run at the same time, in the same cycle or across the boundary of one. Tasks not in the composition are synchronized with nothing.
+	pub fn are_tasks_unsynchronized(
+		&self,
+		task_name: &FullTaskName,
+		other_task_name: &FullTaskName,
+	) -> bool {
+		match (self.index_of(task_name), self.index_of(other_task_name)) {
+			(Some(i), Some(other_i)) => self.are_unsynchronized_at(i, other_i),
+			_ => true,
+		}
+	}
+
+	pub fn are_unsynchronized_at(&self, i: usize, other_i: usize) -> bool {
+		self.components[i] != self.components[other_i]
+			|| !self.happens_before_at(i, other_i)
+				&& !self.happens_before_at(other_i, i)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	extern crate test;
+
+	use super::HappensBefore;
+
+	use crate::{
+		composition::unloaded::{
+			composition_builder::{CompositionBuilder, TaskBuilder},
+			unloaded_composition::UnloadedComposition,
+		},
+		identify::task_name::FullTaskName,
+	};
+
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+	use test::Bencher;
+
+	fn name(task_name: usize) -> FullTaskName {
+		FullTaskName::new("c".to_owned(), task_name.to_string())
+	}
+
+	/// A cycle of `layers` layers of `width` tasks, each with two parents in the layer before. The first layer is a single entrypoint, which waits on the whole last layer.
+	fn generate(layers: usize, width: usize) -> UnloadedComposition {
+		let mut rng = StdRng::seed_from_u64(0);
+		let last_layer = (layers - 2) * width + 1..(layers - 1) * width + 1;
+		let mut entrypoint = TaskBuilder::new("T", "t.ron").entrypoint(true);
+		for parent in last_layer {
+			entrypoint = entrypoint.parent("c", parent.to_string());
+		}
+		let mut builder =
+			CompositionBuilder::new().with_task(name(0), entrypoint);
+		for layer in 1..layers {
+			let previous = if layer == 1 {
+				0..1
+			} else {
+				(layer - 2) * width + 1..(layer - 1) * width + 1
+			};
+			for i in 0..width {
+				let mut task = TaskBuilder::new("T", "t.ron");
+				for _ in 0..2 {
+					task = task.parent(
+						"c",
+						rng.gen_range(previous.clone()).to_string(),
+					);
+				}
+				builder =
+					builder.with_task(name((layer - 1) * width + 1 + i), task);
+			}
+		}
+		builder.build()
+	}
+
+	#[test]
+	fn orders_tasks_within_a_cycle() {
+		//0 begins the cycle, then 1 and 2 run side by side, then 3
+		let composition = CompositionBuilder::new()
+			.with_task(
+				name(0),
+				TaskBuilder::new("T", "t.ron")
+					.entrypoint(true)
+					.parent("c", "3"),
+			)
+			.with_task(name(1), TaskBuilder::new("T", "t.ron").parent("c", "0"))
+			.with_task(name(2), TaskBuilder::new("T", "t.ron").parent("c", "0"))
+			.with_task(
+				name(3),
+				TaskBuilder::new("T", "t.ron")
+					.parent("c", "1")
+					.parent("c", "2"),
+			)
+			.build();
+		let happens_before = HappensBefore::new(&composition);
+		assert!(happens_before.happens_before(&name(0), &name(3)));
+		assert!(!happens_before.happens_before(&name(3), &name(0)));
+		assert!(!happens_before.are_tasks_unsynchronized(&name(1), &name(3)));
+		assert!(happens_before.are_tasks_unsynchronized(&name(1), &name(2)));
+	}
+
+	#[test]
+	fn later_tasks_must_gate_the_next_cycle() {
+		//0 (E) -> 1 (A) -> 2 (C) -> 0, with 3 (B) after 1 and 4 (X), and 4 after 3
+		let composition = CompositionBuilder::new()
+			.with_task(
+				name(0),
+				TaskBuilder::new("T", "t.ron")
+					.entrypoint(true)
+					.parent("c", "2"),
+			)
+			.with_task(name(1), TaskBuilder::new("T", "t.ron").parent("c", "0"))
+			.with_task(name(2), TaskBuilder::new("T", "t.ron").parent("c", "1"))
+			.with_task(
+				name(3),
+				TaskBuilder::new("T", "t.ron")
+					.parent("c", "1")
+					.parent("c", "4"),
+			)
+			.with_task(name(4), TaskBuilder::new("T", "t.ron").parent("c", "3"))
+			.build();
+		let happens_before = HappensBefore::new(&composition);
+		//A is before X within a cycle, but A's next cycle never waits for X
+		assert!(happens_before.happens_before(&name(1), &name(4)));
+		assert!(happens_before.are_tasks_unsynchronized(&name(1), &name(4)));
+		assert!(happens_before.are_tasks_unsynchronized(&name(1), &name(3)));
+		assert!(!happens_before.are_tasks_unsynchronized(&name(1), &name(2)));
+		assert!(!happens_before.are_tasks_unsynchronized(&name(0), &name(2)));
+		assert!(!happens_before.are_tasks_unsynchronized(&name(3), &name(4)));
+	}
+
+	#[bench]
+	fn build_for_4096_tasks(b: &mut Bencher) {
+		let composition = generate(65, 64);
+		b.iter(|| HappensBefore::new(&composition));
+	}
+
+	/// Every pair, as the access check queries them.
+	#[bench]
+	fn query_pairs_of_4096_tasks(b: &mut Bencher) {
+		let composition = generate(65, 64);
+		let happens_before = HappensBefore::new(&composition);
+		let indices: Vec<usize> = (0..4096)
+			.map(|i| happens_before.index_of(&name(i)).unwrap())
+			.collect();
+		b.iter(|| {
+			let mut unsynchronized = 0;
+			for (i, index) in indices.iter().enumerate() {
+				for other_index in &indices[i + 1..] {
+					unsynchronized += happens_before
+						.are_unsynchronized_at(*index, *other_index)
+						as usize;
+				}
+			}
+			unsynchronized
+		});
+	}
+}
@@ -1,6 +1,11 @@
 pub mod access;
+pub mod executor;
 pub mod fulfiller;
 pub mod fulfiller_chain;
+pub mod fulfiller_state;
 pub mod graph;
+pub mod happens_before;
 pub mod possibly_poisoned_mutex;
 pub mod ready;
+pub mod scheduler;
+pub mod work_stealing;
@@ -15,10 +15,12 @@ use std::sync::atomic::{AtomicU64, Ordering};
 ///
 #[derive(Debug)]
 pub struct Ready {
-	//TODO: atomic orderings and general implementation could be made slightly faster; test this on release mode
 	state: AtomicU64,
+	/// Raised by the parents of the fulfiller that owns this `Ready`, and read by that fulfiller when it releases.
 	greatest_prereq: AtomicU64,
 	entrypoint: bool,
+	/// How many times an entrypoint has been released, which is the number of cycles it has run.
+	cycles: AtomicU64,
 }
 
 impl Ready {
@@ -27,24 +29,34 @@ impl Ready {
 			state: AtomicU64::new(0),
 			greatest_prereq: AtomicU64::new(0),
 			entrypoint,
+			cycles: AtomicU64::new(0),
 		}
 	}
 
-	pub(crate) fn release(&self) {
-		self.state.store(
-			self.greatest_prereq.load(Ordering::SeqCst) + 1,
-			Ordering::SeqCst,
-		);
+	pub(crate) fn is_entrypoint(&self) -> bool {
+		self.entrypoint
 	}
 
-	pub(crate) fn load_prerequisite(&self, other: &Self) -> bool {
-		let ostate = other.state.load(Ordering::SeqCst);
-		let greatest_prereq = self.greatest_prereq.load(Ordering::SeqCst);
-		let state = self.state.load(Ordering::SeqCst);
+	/// The number of cycles an entrypoint has run. Always 0 for other tasks.
+	pub(crate) fn cycles(&self) -> u64 {
+		self.cycles.load(Ordering::Relaxed)
+	}
 
-		if ostate > greatest_prereq {
-			self.greatest_prereq.store(ostate, Ordering::SeqCst);
+	/// The release store pairs with the acquire load in [load_prerequisite](Self::load_prerequisite), so that everything a task wrote is visible to the tasks it lets run.
+	pub(crate) fn release(&self) {
+		//load_prerequisite runs on the threads of the parents, so pair with its release
+		let greatest_prereq = self.greatest_prereq.load(Ordering::Acquire);
+		if self.entrypoint {
+			self.cycles.fetch_add(1, Ordering::Relaxed);
 		}
+		self.state.store(greatest_prereq + 1, Ordering::Release);
+	}
+
+	pub(crate) fn load_prerequisite(&self, other: &Self) -> bool {
+		let ostate = other.state.load(Ordering::Acquire);
+		//fetch_max, as two prerequisites completing at once can both check this one
+		self.greatest_prereq.fetch_max(ostate, Ordering::Release);
+		let state = self.state.load(Ordering::Acquire);
 
 		ostate > state
 			|| self.entrypoint && state == 0
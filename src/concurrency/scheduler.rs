@@ -0,0 +1,8 @@
+use std::fmt::Debug;
+
+pub type SchedulerJob = Box<dyn FnOnce() + Send>;
+
+/// Runs fulfiller chains that are not bound to the main thread. Jobs dispatched from a job the scheduler is running are the chains that its task just unblocked.
+pub trait Scheduler: Debug + Send + Sync {
+	fn execute(&self, job: SchedulerJob);
+}
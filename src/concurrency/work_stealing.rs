@@ -0,0 +1,219 @@
+use crate::{
+	concurrency::scheduler::{Scheduler, SchedulerJob},
+	utils::panic_capture,
+};
+
+use log::error;
+
+use std::{
+	cell::Cell,
+	collections::VecDeque,
+	fmt::{self, Formatter},
+	panic::{self, AssertUnwindSafe},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Condvar, Mutex,
+	},
+	thread,
+	time::Duration,
+};
+
+/// How long an idle worker sleeps before looking again. Workers are woken for injected jobs and for surplus local ones, so this only bounds how long a job can wait behind a long task on another worker before it is stolen.
+const IDLE_POLL: Duration = Duration::from_millis(100);
+
+thread_local! {
+	/// The scheduler this thread works for, by address, and its index among the workers.
+	static WORKER: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+#[derive(Default)]
+struct Shared {
+	/// Each worker pops the back of its own queue and steals from the front of the others.
+	locals: Vec<Mutex<VecDeque<SchedulerJob>>>,
+	/// Jobs dispatched from outside the workers, such as the first chains of a run.
+	injector: Mutex<VecDeque<SchedulerJob>>,
+	/// Held to notify `wake`, and by a worker from its last look at the queues until it waits, so that no job is queued unseen in between.
+	sleep: Mutex<()>,
+	wake: Condvar,
+	shutdown: AtomicBool,
+}
+
+impl fmt::Debug for Shared {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("Shared")
+			.field("workers", &self.locals.len())
+			.field("shutdown", &self.shutdown)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Shared {
+	fn id(&self) -> usize {
+		self as *const Self as usize
+	}
+
+	fn find_job(&self, worker: usize) -> Option<SchedulerJob> {
+		if let Some(job) = self.locals[worker].lock().unwrap().pop_back() {
+			return Some(job);
+		}
+		if let Some(job) = self.injector.lock().unwrap().pop_front() {
+			return Some(job);
+		}
+		let count = self.locals.len();
+		for offset in 1..count {
+			let victim = (worker + offset) % count;
+			if let Some(job) = self.locals[victim].lock().unwrap().pop_front() {
+				return Some(job);
+			}
+		}
+		None
+	}
+
+	fn has_job(&self) -> bool {
+		!self.injector.lock().unwrap().is_empty()
+			|| self.locals.iter().any(|v| !v.lock().unwrap().is_empty())
+	}
+
+	fn wake_one(&self) {
+		let _guard = self.sleep.lock().unwrap();
+		self.wake.notify_one();
+	}
+
+	fn work(self: Arc<Self>, worker: usize) {
+		WORKER.with(|v| v.set(Some((self.id(), worker))));
+		loop {
+			match self.find_job(worker) {
+				Some(job) => {
+					if let Err(e) = panic::catch_unwind(AssertUnwindSafe(job)) {
+						//tasks catch their own panics, so this is a bug in custard
+						error!(
+							"Scheduler job panicked: {}",
+							panic_capture::payload_message(&*e)
+								.unwrap_or_default()
+						);
+					}
+				}
+				None => {
+					let guard = self.sleep.lock().unwrap();
+					if self.has_job() {
+						continue;
+					}
+					if self.shutdown.load(Ordering::Acquire) {
+						return;
+					}
+					let _ = self.wake.wait_timeout(guard, IDLE_POLL).unwrap();
+				}
+			}
+		}
+	}
+}
+
+/// A scheduler with a queue per worker. A chain unblocked by a task stays on the worker that ran the task, which picks it up as soon as it is free, while idle workers steal anything that is left waiting.
+#[derive(Debug)]
+pub struct WorkStealingScheduler {
+	shared: Arc<Shared>,
+}
+
+impl WorkStealingScheduler {
+	pub fn new(workers: usize) -> Self {
+		let shared = Arc::new(Shared {
+			locals: (0..workers.max(1))
+				.map(|_| Mutex::new(VecDeque::new()))
+				.collect(),
+			..Default::default()
+		});
+		for worker in 0..shared.locals.len() {
+			let shared = shared.clone();
+			thread::Builder::new()
+				.name(format!("custard-worker-{}", worker))
+				.spawn(move || shared.work(worker))
+				.unwrap();
+		}
+		Self { shared }
+	}
+}
+
+impl Scheduler for WorkStealingScheduler {
+	fn execute(&self, job: SchedulerJob) {
+		let local = WORKER.with(|v| v.get()).and_then(|(id, worker)| {
+			(id == self.shared.id()).then_some(worker)
+		});
+		match local {
+			Some(worker) => {
+				let mut queue = self.shared.locals[worker].lock().unwrap();
+				queue.push_back(job);
+				//the worker takes the first job itself once it is free, so only wake others for the surplus
+				if queue.len() > 1 {
+					std::mem::drop(queue);
+					self.shared.wake_one();
+				}
+			}
+			None => {
+				self.shared.injector.lock().unwrap().push_back(job);
+				self.shared.wake_one();
+			}
+		}
+	}
+}
+
+impl Drop for WorkStealingScheduler {
+	fn drop(&mut self) {
+		//workers finish what is queued, then exit. Not joined, as the last reference can be dropped by a job
+		self.shared.shutdown.store(true, Ordering::Release);
+		let _guard = self.shared.sleep.lock().unwrap();
+		self.shared.wake.notify_all();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Shared, WorkStealingScheduler, WORKER};
+
+	use crate::concurrency::scheduler::Scheduler;
+
+	use std::{
+		collections::VecDeque,
+		sync::{mpsc, Arc, Mutex},
+	};
+
+	#[test]
+	fn runs_nested_jobs() {
+		let scheduler = Arc::new(WorkStealingScheduler::new(4));
+		let (sender, receiver) = mpsc::channel();
+		for i in 0..32 {
+			let inner_scheduler = scheduler.clone();
+			let sender = sender.clone();
+			scheduler.execute(Box::new(move || {
+				let sender = sender.clone();
+				inner_scheduler
+					.execute(Box::new(move || sender.send(i).unwrap()));
+			}));
+		}
+		let mut received: Vec<i32> = receiver.iter().take(32).collect();
+		received.sort();
+		assert_eq!(received, (0..32).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn jobs_from_workers_stay_local() {
+		//no threads, so nothing is run or stolen while the queues are inspected
+		let scheduler = WorkStealingScheduler {
+			shared: Arc::new(Shared {
+				locals: (0..2).map(|_| Mutex::new(VecDeque::new())).collect(),
+				..Default::default()
+			}),
+		};
+		WORKER.with(|v| v.set(Some((scheduler.shared.id(), 1))));
+		scheduler.execute(Box::new(|| {}));
+		assert_eq!(scheduler.shared.locals[1].lock().unwrap().len(), 1);
+		assert!(scheduler.shared.injector.lock().unwrap().is_empty());
+
+		WORKER.with(|v| v.set(None));
+		scheduler.execute(Box::new(|| {}));
+		assert_eq!(scheduler.shared.injector.lock().unwrap().len(), 1);
+
+		assert!(scheduler.shared.find_job(1).is_some()); //its own
+		assert!(scheduler.shared.find_job(1).is_some()); //injected
+		assert!(scheduler.shared.find_job(0).is_none());
+	}
+}
@@ -1,27 +1,37 @@
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 
 use crate::{
 	composition::{
-		loaded::{
-			loaded_composition::{Checked, LoadedComposition},
-			loaded_datachunk::LoadedDatachunk,
+		loaded::loaded_composition::{Checked, LoadedComposition},
+		unloaded::{
+			task_edits::TaskEdits, unloaded_composition::UnloadedComposition,
 		},
-		unloaded::unloaded_composition::UnloadedComposition,
 	},
 	concurrency::{
-		fulfiller::{Fulfiller, Quit},
+		executor::Executor, fulfiller::Quit,
 		possibly_poisoned_mutex::PossiblyPoisonedMutex,
 	},
 	dylib_management::safe_library::safe_library::{
 		DebugMode, LibraryRecompile,
 	},
-	identify::{crate_name::CrateName, datachunk_name::DatachunkName},
+	errors::custard_error::CustardError,
+	identify::{
+		crate_name::CrateName, datachunk_name::FullDatachunkName,
+		task_name::FullTaskName,
+	},
 	instance_control_flow::InstanceControlFlow,
+	recording::{
+		checkpoint::Checkpoint, cycle_hook::CycleHook, recorder::Recorder,
+		replayer::Replayer,
+	},
+	run_options::RunOptions,
+	utils::error_report::report,
 };
 
 use std::{
 	cell::RefCell,
 	collections::{BTreeMap, BTreeSet},
+	path::Path,
 	rc::Rc,
 	sync::{Arc, Mutex},
 };
@@ -37,6 +47,13 @@ pub struct CustardInstance {
 	settings: CustardInstanceSettings,
 	unloaded_composition: UnloadedComposition,
 	loaded_composition: Option<LoadedComposition>,
+	/// Recorders and replayers, reattached whenever the loaded composition is rebuilt.
+	cycle_hooks: Vec<Arc<dyn CycleHook>>,
+	run_options: RunOptions,
+	/// Cycles run by compositions that were since replaced by a reload or task edits, which count towards `run_options`.
+	completed_cycles: u64,
+	/// Drives async tasks. Declared before `drop_list` so that pending futures are dropped while their libraries are still loaded.
+	executor: Arc<Executor>,
 	#[allow(unused)]
 	drop_list: Rc<RefCell<Vec<libloading::Library>>>,
 }
@@ -72,6 +89,9 @@ impl CustardInstance {
 
 		let checked =
 			LoadedComposition::check(&root_composition_unloaded).unwrap();
+		for lint in LoadedComposition::lint(&root_composition_unloaded) {
+			warn!("{}", lint);
+		}
 
 		let root_composition = LoadedComposition::new(
 			quit,
@@ -89,13 +109,47 @@ impl CustardInstance {
 		Self {
 			settings,
 			drop_list,
+			executor: Arc::new(Executor::new()),
+			cycle_hooks: vec![],
+			run_options: RunOptions::default(),
+			completed_cycles: 0,
 			unloaded_composition: root_composition_unloaded,
 			loaded_composition: Some(root_composition),
 		}
 	}
 
+	/// Create a new `CustardInstance` and restore the datachunk and task states saved by [checkpoint](Self::checkpoint) at `path`, to resume a run that was interrupted.
+	pub fn from_checkpoint(
+		settings: CustardInstanceSettings,
+		path: impl AsRef<Path>,
+	) -> Result<Self, CustardError> {
+		let mut ret = Self::new(settings);
+		ret.restore(path)?;
+		Ok(ret)
+	}
+
+	/// Save the state of every datachunk and task that implements `save_state` to `path`. Use this from an entrypoint before calling [run](Self::run); tasks can request a checkpoint at runtime by returning `TaskControlFlow::Checkpoint`, which is taken once every task has stopped.
+	pub fn checkpoint(
+		&self,
+		path: impl AsRef<Path>,
+	) -> Result<(), CustardError> {
+		Checkpoint::capture(
+			self.loaded_composition.as_ref().unwrap().crates.get(),
+		)
+		.save(path)
+	}
+
+	/// Restore the states saved by [checkpoint](Self::checkpoint) at `path`. Datachunks and tasks missing from the checkpoint keep their current state.
+	pub fn restore(
+		&mut self,
+		path: impl AsRef<Path>,
+	) -> Result<(), CustardError> {
+		Checkpoint::load(path)?
+			.restore(self.loaded_composition.as_ref().unwrap().crates.get())
+	}
+
 	/// Drop self and all dynamic libraries, saving only the settings and barrier for new instance.
-	pub(crate) fn full_reload(self) {
+	pub(crate) fn full_reload(mut self) {
 		let settings = self.settings.clone();
 		let quit = self
 			.loaded_composition
@@ -103,14 +157,22 @@ impl CustardInstance {
 			.unwrap()
 			.task_completion
 			.clone();
+		let cycle_hooks = std::mem::take(&mut self.cycle_hooks);
+		let run_options = self.run_options.clone();
+		let completed_cycles = self.completed_cycles
+			+ self.loaded_composition.as_ref().unwrap().cycles();
 
 		std::mem::drop(self);
 
-		let ret = Self::new_with_quit(settings, Some(quit));
+		let mut ret = Self::new_with_quit(settings, Some(quit));
+		ret.cycle_hooks = cycle_hooks;
+		ret.run_options = run_options;
+		ret.completed_cycles = completed_cycles;
+		ret.reattach_cycle_hooks();
 		ret.run();
 	}
 
-	/// Replace loaded composition with a fresh one, but do not drop libraries. Because not all crates are reloaded, the `drop_list` is kept. Note that `reload_for_sure` is not the be all and end all of reloading. If a crate's old composition does not align with its new composition, it will be reloaded as well.
+	/// Replace loaded composition with a fresh one, but do not drop libraries. Because not all crates are reloaded, the `drop_list` is kept. Note that `reload_for_sure` is not the be all and end all of reloading. If a crate's old composition does not align with its new composition, it will be reloaded as well. So is every instance of a library that is reloaded; see [crates_to_reload](UnloadedComposition::crates_to_reload).
 	pub(crate) fn partial_reload(
 		mut self,
 		new_unloaded_composition: UnloadedComposition,
@@ -120,18 +182,22 @@ impl CustardInstance {
 		//TODO: so, so, so much testing
 
 		let old_composition = self.loaded_composition.take().unwrap();
+		self.completed_cycles += old_composition.cycles();
 		let mut old_crates = BTreeMap::new();
+		let mut old_libraries = BTreeMap::new();
+		let to_reload = self
+			.unloaded_composition
+			.crates_to_reload(&new_unloaded_composition, &reload_for_sure);
 
 		for (crate_name, old_crate) in
 			unsafe { old_composition.crates.get_mut() }
 		{
 			let crate_name = crate_name.clone();
-			let reload = reload_for_sure.contains(&crate_name);
 			let old_unloaded_crate =
 				self.unloaded_composition.crates.get(&crate_name);
 			let new_unloaded_crate =
 				new_unloaded_composition.crates.get(&crate_name);
-			if !(!reload && old_unloaded_crate == new_unloaded_crate) {
+			if to_reload.contains(&crate_name) {
 				let delta_tasks =
 					new_unloaded_crate.as_ref().unwrap().tasks.len()
 						- old_unloaded_crate.as_ref().unwrap().tasks.len();
@@ -140,29 +206,13 @@ impl CustardInstance {
 					.begin_fulfillers(delta_tasks as isize);
 				continue;
 			}
-			let mut old_tasks = BTreeMap::new();
-
-			for (task_name, old_fulfiller) in &old_crate.tasks {
-				let task_name = task_name.clone();
-				let mut_fulfiller = unsafe {
-					&mut *(Arc::as_ptr(&old_fulfiller) as *mut Fulfiller)
-				};
-				let old_task = mut_fulfiller.task.take().unwrap();
-				old_tasks.insert(task_name, old_task);
-			}
-
-			let mut old_datachunks = BTreeMap::new();
-
-			for (datachunk_name, old_datachunk) in unsafe {
-				&mut *(&old_crate.datachunks as *const _
-					as *mut BTreeMap<DatachunkName, Option<LoadedDatachunk>>)
-			} {
-				let datachunk_name = datachunk_name.clone();
-				old_datachunks
-					.insert(datachunk_name, old_datachunk.take().unwrap());
-			}
-
-			old_crates.insert(crate_name, (old_tasks, old_datachunks));
+			old_crates.insert(crate_name.clone(), old_crate.take_contents());
+			let library_name =
+				old_unloaded_crate.unwrap().library_name(&crate_name);
+			old_libraries.insert(
+				library_name.clone(),
+				old_composition.user_libraries[library_name].clone(),
+			);
 		}
 
 		self.unloaded_composition = new_unloaded_composition;
@@ -175,18 +225,186 @@ impl CustardInstance {
 				self.settings.debug_mode.clone(),
 				self.drop_list.clone(),
 				checked,
-				old_crates,
+				&mut old_crates,
+				old_libraries,
 			)
 			.unwrap(),
 		);
+		self.reattach_cycle_hooks();
 
 		info!("Partial reload: rerunning instance.");
 		self.run();
 	}
 
-	/// Consume self, giving up control to the instance. Any errors past this point are unhandleable, so ensure that any reloads come only after thoroughly checking the new composition.
+	/// Add or remove task instances of already loaded task types. The edits are checked against the current composition first, and nothing changes if they are invalid. Use this from an entrypoint before calling [run](Self::run); tasks can request the same thing at runtime by returning `TaskControlFlow::EditTasks`.
+	pub fn edit_tasks(
+		&mut self,
+		edits: &TaskEdits,
+	) -> Result<(), CustardError> {
+		let (edited_composition, checked) = self
+			.loaded_composition
+			.as_ref()
+			.unwrap()
+			.check_task_edits(&self.unloaded_composition, edits)?;
+		self.apply_task_edits(edited_composition, checked, edits)?;
+		Ok(())
+	}
+
+	/// Rebuild the loaded composition from `edited_composition`, reusing every task, datachunk and library that is not removed. Fulfillers and fulfiller chains are regenerated, as they are in a partial reload. If loading fails, or the cycle hooks do not fit the edited composition, the current composition is left as it was.
+	fn apply_task_edits(
+		&mut self,
+		edited_composition: UnloadedComposition,
+		checked: Checked,
+		edits: &TaskEdits,
+	) -> Result<(), CustardError> {
+		LoadedComposition::check_cycle_hooks(
+			&edited_composition,
+			&self.cycle_hooks,
+		)?;
+		let old_composition = self.loaded_composition.as_mut().unwrap();
+		let mut old_crates = BTreeMap::new();
+		//kept apart, so that a removed task is not reused by an added task of the same name
+		let mut removed = BTreeMap::new();
+
+		for (crate_name, old_crate) in
+			unsafe { old_composition.crates.get_mut() }
+		{
+			let (mut old_tasks, old_datachunks) = old_crate.take_contents();
+			let mut removed_tasks = BTreeMap::new();
+			for task_name in &edits.remove {
+				if &task_name.crate_name != crate_name {
+					continue;
+				}
+				if let Some(v) = old_tasks.remove(&task_name.task_name) {
+					removed_tasks.insert(task_name.task_name.clone(), v);
+				}
+			}
+			removed
+				.insert(crate_name.clone(), (removed_tasks, BTreeMap::new()));
+			old_crates.insert(crate_name.clone(), (old_tasks, old_datachunks));
+		}
+
+		let new_composition = match LoadedComposition::new_with_baggage(
+			Some(old_composition.task_completion.clone()),
+			&edited_composition,
+			self.settings.recompile.clone(),
+			self.settings.debug_mode.clone(),
+			self.drop_list.clone(),
+			checked,
+			&mut old_crates,
+			old_composition.user_libraries.clone(),
+		) {
+			Ok(v) => v,
+			Err(e) => {
+				for (crate_name, old_crate) in
+					unsafe { old_composition.crates.get_mut() }
+				{
+					old_crate.put_back_contents(
+						removed.get_mut(crate_name).unwrap(),
+					);
+					if let Some(contents) = old_crates.get_mut(crate_name) {
+						old_crate.put_back_contents(contents);
+					}
+				}
+				return Err(e);
+			}
+		};
+
+		self.completed_cycles += old_composition.cycles();
+		old_composition.task_completion.resize_fulfillers(
+			edits.add.len() as isize - edits.remove.len() as isize,
+		);
+		self.unloaded_composition = edited_composition;
+		self.loaded_composition = Some(new_composition);
+		self.reattach_cycle_hooks();
+
+		Ok(())
+	}
+
+	/// Write the state of `datachunks` to a log at `path` each time `boundary` begins a cycle. The datachunks must implement [save_state](crate::user_types::datachunk::Datachunkable::save_state), and reading them must not conflict with any task that can run alongside `boundary`.
+	pub fn record(
+		&mut self,
+		boundary: FullTaskName,
+		datachunks: BTreeSet<FullDatachunkName>,
+		path: impl AsRef<Path>,
+	) -> Result<(), CustardError> {
+		let recorder = Recorder::new(path, boundary, datachunks)?;
+		self.add_cycle_hook(Arc::new(recorder))
+	}
+
+	/// Restore the datachunk states logged by [record](Self::record) from `path`, one frame each time the recorded boundary begins a cycle. Tasks that write the replayed datachunks are disabled for as long as the instance lives, so they may not write any other datachunk; the replay is rejected if one does.
+	pub fn replay(
+		&mut self,
+		path: impl AsRef<Path>,
+	) -> Result<(), CustardError> {
+		let replayer = Replayer::new(path)?;
+		self.add_cycle_hook(Arc::new(replayer))
+	}
+
+	fn add_cycle_hook(
+		&mut self,
+		hook: Arc<dyn CycleHook>,
+	) -> Result<(), CustardError> {
+		self.cycle_hooks.push(hook);
+		let ret = self.attach_cycle_hooks();
+		if ret.is_err() {
+			self.cycle_hooks.pop();
+		}
+		ret
+	}
+
+	/// Attach the cycle hooks to a reloaded composition. A hook that no longer fits, for example because its boundary task was removed, is detached rather than stopping the instance.
+	fn reattach_cycle_hooks(&mut self) {
+		for hook in std::mem::take(&mut self.cycle_hooks) {
+			if let Err(e) = self.add_cycle_hook(hook) {
+				error!("Detaching a cycle hook: {}", report(&e));
+			}
+		}
+	}
+
+	fn attach_cycle_hooks(&mut self) -> Result<(), CustardError> {
+		if self.cycle_hooks.is_empty() {
+			return Ok(());
+		}
+		self.loaded_composition
+			.as_mut()
+			.unwrap()
+			.attach_cycle_hooks(&self.unloaded_composition, &self.cycle_hooks)
+	}
+
+	/// Let every fulfiller that has not errored run again.
+	fn resume_fulfillers(&mut self) {
+		let comp_ref = self.loaded_composition.as_mut().unwrap();
+
+		for chain in &*comp_ref.fulfiller_chains {
+			for fulfiller in &chain.chain {
+				if let Some(fulfiller) = fulfiller.upgrade() {
+					fulfiller.state.resume();
+				}
+			}
+		}
+	}
+
+	/// [run](Self::run) until one of the limits in `run_options` is reached, then stop cleanly. Useful for benchmarks and integration tests.
+	pub fn run_with_options(mut self, run_options: RunOptions) {
+		self.run_options = run_options;
+		self.run();
+	}
+
+	/// Consume self, giving up control to the instance. Any errors past this point are unhandleable, so ensure that any reloads come only after thoroughly checking the new composition. Tasks marked `main_thread` in the composition run on the thread that calls this.
 	pub fn run(mut self) {
-		let control_flow = self.loaded_composition.as_ref().unwrap().run();
+		let run_options = RunOptions {
+			max_cycles: self
+				.run_options
+				.max_cycles
+				.map(|v| v.saturating_sub(self.completed_cycles)),
+			deadline: self.run_options.deadline,
+		};
+		let control_flow = self
+			.loaded_composition
+			.as_ref()
+			.unwrap()
+			.run(&self.executor, run_options);
 		let loaded_composition = self.loaded_composition.as_mut().unwrap();
 		if unsafe { loaded_composition.task_completion.reset() } == 0 {
 			return;
@@ -196,10 +414,57 @@ impl CustardInstance {
 			Mutex::new(InstanceControlFlow::Continue),
 		));
 
+		self.follow(control_flow);
+	}
+
+	/// Acts on the control flow a run ended with, usually by running again.
+	fn follow(mut self, control_flow: InstanceControlFlow) {
 		match control_flow {
+			InstanceControlFlow::Checkpoint(path, then) => {
+				if let Err(e) = self.checkpoint(&*path) {
+					error!("{}", report(&e));
+				}
+				if let InstanceControlFlow::Continue = *then {
+					self.resume_fulfillers();
+
+					info!("Checkpoint saved: rerunning instance.");
+					self.run();
+				} else {
+					self.follow(*then);
+				}
+			}
 			InstanceControlFlow::Continue => {
 				println!("Relaxed exit");
 			}
+			InstanceControlFlow::EditTasks(edits) => {
+				match self
+					.loaded_composition
+					.as_ref()
+					.unwrap()
+					.check_task_edits(&self.unloaded_composition, &edits)
+				{
+					Ok((edited_composition, checked)) => {
+						for lint in LoadedComposition::lint(&edited_composition)
+						{
+							warn!("{}", lint);
+						}
+						if let Err(e) = self.apply_task_edits(
+							edited_composition,
+							checked,
+							&edits,
+						) {
+							error!("{}", report(&e));
+							self.resume_fulfillers();
+						}
+					}
+					Err(e) => {
+						error!("{}", report(&e));
+						self.resume_fulfillers();
+					}
+				};
+				info!("Task edits: rerunning instance.");
+				self.run();
+			}
 			InstanceControlFlow::FullReload => self.full_reload(),
 			InstanceControlFlow::PartialReload(reload_for_sure) => {
 				let prospective_composition = unsafe {
@@ -213,6 +478,11 @@ impl CustardInstance {
 				};
 				match LoadedComposition::check(&prospective_composition) {
 					Ok(v) => {
+						for lint in
+							LoadedComposition::lint(&prospective_composition)
+						{
+							warn!("{}", lint);
+						}
 						self.partial_reload(
 							prospective_composition,
 							v,
@@ -220,7 +490,7 @@ impl CustardInstance {
 						)
 					}
 					Err(e) => {
-						error!("{}", e);
+						error!("{}", report(&e));
 						*self
 							.loaded_composition
 							.as_ref()
@@ -232,17 +502,7 @@ impl CustardInstance {
 				};
 			}
 			InstanceControlFlow::RecreateThreadpool => {
-				let comp_ref = self.loaded_composition.as_mut().unwrap();
-
-				for chain in &*comp_ref.fulfiller_chains {
-					for fulfiller in &chain.chain {
-						if let Some(fulfiller) = fulfiller.upgrade() {
-							if !*fulfiller.error.lock().unwrap() {
-								*fulfiller.cease.lock().unwrap() = false;
-							}
-						}
-					}
-				}
+				self.resume_fulfillers();
 
 				info!("Rerunning instance.");
 				self.run();
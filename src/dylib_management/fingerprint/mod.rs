@@ -0,0 +1,281 @@
+use cargo_metadata::{Metadata, MetadataCommand};
+use log::info;
+
+use crate::{
+	dylib_management::safe_library::safe_library::{self, DebugMode},
+	errors::custard_error::CustardError,
+	identify::{crate_name::CrateName, custard_name::CustardName},
+};
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	env, fs,
+	hash::Hasher,
+	io,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// Directories that never hold sources, skipped when fingerprinting.
+const SKIPPED_DIRECTORIES: [&str; 2] = ["target", "custard_dylib_cache"];
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output does not change between Rust releases, so a stored fingerprint stays comparable after custard itself is rebuilt.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+	fn default() -> Self {
+		Self(0xcbf2_9ce4_8422_2325)
+	}
+}
+
+impl Hasher for Fnv1a {
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= *byte as u64;
+			self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+		}
+	}
+
+	fn finish(&self) -> u64 {
+		self.0
+	}
+}
+
+/// Feed `bytes` to `hasher` behind their length, so that consecutive fields cannot run into one another.
+fn write_field(hasher: &mut impl Hasher, bytes: &[u8]) {
+	hasher.write(&(bytes.len() as u64).to_le_bytes());
+	hasher.write(bytes);
+}
+
+/// Fingerprint every crate in `names` and return those whose fingerprint differs from the one stored next to their cached library, or that have no cached library at all. A fingerprint covers the crate's sources, those of every path dependency it reaches, `Cargo.lock`, the build profile and the version of `rustc`.
+pub fn changed<'a>(
+	names: impl IntoIterator<Item = &'a CrateName>,
+	debug: &DebugMode,
+) -> Result<BTreeMap<CrateName, u64>, CustardError> {
+	let metadata_error =
+		|e: Box<dyn std::error::Error>| CustardError::Metadata { source: e };
+	let metadata = MetadataCommand::new()
+		.no_deps()
+		.exec()
+		.map_err(|e| metadata_error(Box::new(e)))?;
+	let lock_file = fs::read(metadata.workspace_root.join("Cargo.lock"))
+		.unwrap_or_default();
+	let rustc_version =
+		rustc_version().map_err(|e| metadata_error(Box::new(e)))?;
+
+	let mut ret = BTreeMap::new();
+	for name in names {
+		let fingerprint_error = |e: io::Error| CustardError::Compile {
+			crate_names: vec![name.clone()],
+			source: Box::new(e),
+		};
+		let package = metadata.packages.iter().find(|package| {
+			package.name.replace("-", "_") == name.get().replace("-", "_")
+		});
+		let package_dir = match package {
+			Some(v) => v.manifest_path.parent().unwrap().as_std_path(),
+			None => {
+				return Err(fingerprint_error(io::Error::new(
+					io::ErrorKind::NotFound,
+					"crate is not a package in this workspace",
+				)))
+			}
+		};
+
+		let mut hasher = Fnv1a::default();
+		write_field(&mut hasher, &lock_file);
+		write_field(&mut hasher, &rustc_version);
+		hash_package(package_dir, debug, &mut hasher)
+			.map_err(fingerprint_error)?;
+		for dependency_dir in
+			path_dependencies(package_dir, &metadata).map_err(metadata_error)?
+		{
+			hash_directory(&dependency_dir, &dependency_dir, &mut hasher)
+				.map_err(fingerprint_error)?;
+		}
+		let fingerprint = hasher.finish();
+
+		let library_name = safe_library::library_file_name(name);
+		let cached =
+			Path::new(&format!("custard_dylib_cache/{}", library_name))
+				.exists();
+		if !cached || stored(name) != Some(fingerprint) {
+			info!("Crate {:?} changed since it was last built.", name);
+			ret.insert(name.clone(), fingerprint);
+		}
+	}
+	Ok(ret)
+}
+
+/// The output of `rustc -vV`, which names the compiler's version, commit and host. Libraries built by another compiler are rebuilt.
+fn rustc_version() -> io::Result<Vec<u8>> {
+	let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+	let output = Command::new(rustc).arg("-vV").output()?;
+	if !output.status.success() {
+		return Err(io::Error::other(format!(
+			"rustc -vV exited with {}",
+			output.status
+		)));
+	}
+	Ok(output.stdout)
+}
+
+/// The directories of every path dependency reachable from the package in `package_dir`, sorted. Packages outside the workspace are looked up with their own `cargo metadata`, so that their path dependencies are followed too.
+fn path_dependencies(
+	package_dir: &Path,
+	workspace: &Metadata,
+) -> Result<BTreeSet<PathBuf>, Box<dyn std::error::Error>> {
+	let mut ret = BTreeSet::new();
+	let mut pending = vec![package_dir.to_owned()];
+	while let Some(dir) = pending.pop() {
+		let manifest_path = dir.join("Cargo.toml");
+		let outside_workspace;
+		let metadata = if workspace
+			.packages
+			.iter()
+			.any(|package| package.manifest_path == manifest_path)
+		{
+			workspace
+		} else {
+			outside_workspace = MetadataCommand::new()
+				.manifest_path(&manifest_path)
+				.no_deps()
+				.exec()?;
+			&outside_workspace
+		};
+		let package = metadata
+			.packages
+			.iter()
+			.find(|package| package.manifest_path == manifest_path);
+		for dependency in package.into_iter().flat_map(|v| &v.dependencies) {
+			if let Some(path) = &dependency.path {
+				let path = path.as_std_path().to_owned();
+				if path != package_dir && ret.insert(path.clone()) {
+					pending.push(path);
+				}
+			}
+		}
+	}
+	Ok(ret)
+}
+
+fn fingerprint_path(name: &CrateName) -> String {
+	format!(
+		"custard_dylib_cache/{}.fingerprint",
+		safe_library::library_file_name(name)
+	)
+}
+
+fn stored(name: &CrateName) -> Option<u64> {
+	fs::read_to_string(fingerprint_path(name))
+		.ok()?
+		.trim()
+		.parse()
+		.ok()
+}
+
+/// Record the fingerprint of a freshly built crate.
+pub fn store(name: &CrateName, fingerprint: u64) -> Result<(), CustardError> {
+	fs::write(fingerprint_path(name), fingerprint.to_string()).map_err(|e| {
+		CustardError::Compile {
+			crate_names: vec![name.clone()],
+			source: Box::new(e),
+		}
+	})
+}
+
+/// Hash the build profile and every file in the package, including its manifest. Nested packages are left to their own fingerprints.
+fn hash_package(
+	package_dir: &Path,
+	debug: &DebugMode,
+	hasher: &mut impl Hasher,
+) -> io::Result<()> {
+	let profile = match debug {
+		DebugMode::Debug => "debug",
+		DebugMode::Release => "release",
+	};
+	write_field(hasher, profile.as_bytes());
+	hash_directory(package_dir, package_dir, hasher)
+}
+
+fn hash_directory(
+	package_dir: &Path,
+	dir: &Path,
+	hasher: &mut impl Hasher,
+) -> io::Result<()> {
+	let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+	entries.sort_by_key(|entry| entry.file_name());
+	for entry in entries {
+		let path = entry.path();
+		let file_name = entry.file_name();
+		let file_name = file_name.to_string_lossy();
+		if file_name.starts_with('.') {
+			continue;
+		}
+		if entry.file_type()?.is_dir() {
+			if SKIPPED_DIRECTORIES.contains(&file_name.as_ref())
+				|| path.join("Cargo.toml").exists()
+			{
+				continue;
+			}
+			hash_directory(package_dir, &path, hasher)?;
+		} else {
+			let relative: Vec<_> = path
+				.strip_prefix(package_dir)
+				.unwrap()
+				.components()
+				.map(|v| v.as_os_str().to_string_lossy())
+				.collect();
+			write_field(hasher, relative.join("/").as_bytes());
+			write_field(hasher, &fs::read(&path)?);
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{hash_package, Fnv1a};
+
+	use crate::dylib_management::safe_library::safe_library::DebugMode;
+
+	use std::{fs, hash::Hasher, path::Path};
+
+	fn fingerprint(dir: &Path, debug: &DebugMode) -> u64 {
+		let mut hasher = Fnv1a::default();
+		hash_package(dir, debug, &mut hasher).unwrap();
+		hasher.finish()
+	}
+
+	#[test]
+	fn fingerprint_tracks_sources_and_profile() {
+		let dir = std::env::temp_dir()
+			.join(format!("custard_fingerprint_{}", std::process::id()));
+		fs::create_dir_all(dir.join("src")).unwrap();
+		fs::create_dir_all(dir.join("target")).unwrap();
+		fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+		fs::write(dir.join("src/lib.rs"), "pub fn a() {}").unwrap();
+
+		let original = fingerprint(&dir, &DebugMode::Debug);
+		fs::write(dir.join("target/artifact"), "built").unwrap();
+		assert_eq!(fingerprint(&dir, &DebugMode::Debug), original);
+		assert_ne!(fingerprint(&dir, &DebugMode::Release), original);
+
+		fs::write(dir.join("src/lib.rs"), "pub fn b() {}").unwrap();
+		assert_ne!(fingerprint(&dir, &DebugMode::Debug), original);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn fnv1a_matches_reference_values() {
+		let hash = |bytes: &[u8]| {
+			let mut hasher = Fnv1a::default();
+			hasher.write(bytes);
+			hasher.finish()
+		};
+		assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+		assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+		assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+	}
+}
@@ -1,2 +1,3 @@
+pub mod fingerprint;
 pub mod runtime_compile;
 pub mod safe_library;
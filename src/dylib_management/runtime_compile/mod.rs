@@ -1,29 +1,51 @@
-use std::{error::Error, fs, process::Command};
+use std::{fs, process::Command};
 
 use crate::{
-	dylib_management::safe_library::safe_library::DebugMode,
+	dylib_management::safe_library::safe_library::{self, DebugMode},
+	errors::custard_error::CustardError,
 	identify::{crate_name::CrateName, custard_name::CustardName},
 };
 
+/// Build every crate in `names` with a single `cargo build`, so that the workspace is only resolved once, then copy each artifact into `custard_dylib_cache`. Fails, copying nothing, if the build does, with an error that names the whole batch.
 pub fn compile(
-	name: CrateName,
-	library_name: &str,
+	names: &[CrateName],
 	debug: DebugMode,
-) -> Result<(), Box<dyn Error>> {
-	let mut handle = Command::new("cargo")
-		.arg("build")
-		.arg("-p")
-		.arg(name.get())
-		.spawn()?;
-	handle.wait()?;
+) -> Result<(), CustardError> {
+	if names.is_empty() {
+		return Ok(());
+	}
+	let compile_error = |names: &[CrateName]| {
+		let names = names.to_vec();
+		move |e: std::io::Error| CustardError::Compile {
+			crate_names: names,
+			source: Box::new(e),
+		}
+	};
+	let mut command = Command::new("cargo");
+	command.arg("build");
+	for name in names {
+		command.arg("-p").arg(name.get());
+	}
+	let mut handle = command.spawn().map_err(compile_error(names))?;
+	let status = handle.wait().map_err(compile_error(names))?;
+	if !status.success() {
+		return Err(CustardError::Compile {
+			crate_names: names.to_vec(),
+			source: format!("cargo build exited with {}", status).into(),
+		});
+	}
 	let debug_release = if let DebugMode::Debug = debug {
 		"debug"
 	} else {
 		"release"
 	};
-	fs::copy(
-		format!("target/{}/{}", debug_release, library_name),
-		format!("custard_dylib_cache/{}", library_name),
-	)?;
+	for name in names {
+		let library_name = safe_library::library_file_name(name);
+		fs::copy(
+			format!("target/{}/{}", debug_release, library_name),
+			format!("custard_dylib_cache/{}", library_name),
+		)
+		.map_err(compile_error(std::slice::from_ref(name)))?;
+	}
 	Ok(())
 }
@@ -1,6 +1,7 @@
 use std::{cell::RefCell, error::Error, rc::Rc};
 
-use log::info;
+use cargo_metadata::Version;
+use log::{info, warn};
 
 use crate::{
 	dylib_management::safe_library::{
@@ -9,6 +10,7 @@ use crate::{
 			self, DebugMode, LibraryDrop, LibraryRecompile, SafeLibrary,
 		},
 	},
+	errors::custard_error::CustardError,
 	identify::crate_name::CrateName,
 };
 
@@ -20,6 +22,9 @@ pub type UnloadedDatachunkContentsFunctionType =
 pub type UnloadedDatachunkContentsFunctionReturnType =
 	Box<FFIResult<String, Box<dyn Error>>>;
 
+/// Returns the package version the library was built as, usually `env!("CARGO_PKG_VERSION")`.
+pub type VersionFunctionType = extern "C" fn() -> Box<String>;
+
 pub type UnloadedTaskContentsFunctionType =
 	extern "C" fn(Box<String>) -> UnloadedTaskContentsFunctionReturnType;
 pub type UnloadedTaskContentsFunctionReturnType =
@@ -33,6 +38,8 @@ pub(crate) struct CoreLibrarySymbols<'lib> {
 		libloading::Symbol<'lib, UnloadedDatachunkContentsFunctionType>,
 	pub(crate) unloaded_task_contents:
 		libloading::Symbol<'lib, UnloadedTaskContentsFunctionType>,
+	/// Optional, so that libraries built before versions were embedded still load.
+	pub(crate) version: Option<libloading::Symbol<'lib, VersionFunctionType>>,
 }
 
 #[derive(Debug)]
@@ -61,13 +68,16 @@ impl<'lib> SafeLibrary for CoreLibrary<'lib> {
 		recompile: LibraryRecompile,
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
-	) -> Result<Self, Box<dyn Error>> {
+	) -> Result<Self, CustardError> {
 		println!("constructing CoreLibrary");
-		let lib = Some(safe_library::load_crate_as_library(
-			name.clone(),
-			recompile,
-			debug,
-		)?);
+		let lib = Some(
+			safe_library::load_crate_as_library(
+				name.clone(),
+				recompile,
+				debug,
+			)?
+			.0,
+		);
 
 		println!("done loading");
 
@@ -81,27 +91,37 @@ impl<'lib> SafeLibrary for CoreLibrary<'lib> {
 		unsafe {
 			let lib =
 				&*(ret.lib.as_ref().unwrap() as *const libloading::Library);
+			let library_error = |e: libloading::Error| CustardError::Library {
+				crate_name: ret.name.clone(),
+				source: Box::new(e),
+			};
 
 			info!("Loading library symbols.");
 
 			info!("Getting composition getter.");
-			let composition = lib.get(b"__custard_composition__")?;
+			let composition =
+				lib.get(b"__custard_composition__").map_err(library_error)?;
 			info!("Got composition getter.");
 
 			info!("Getting unloaded datachunk getter.");
-			let unloaded_datachunk_contents =
-				lib.get(b"__custard_unloaded_datachunk_contents__")?;
+			let unloaded_datachunk_contents = lib
+				.get(b"__custard_unloaded_datachunk_contents__")
+				.map_err(library_error)?;
 			info!("Got unloaded datachunk getter.");
 
 			info!("Getting unloaded task getter.");
-			let unloaded_task_contents =
-				lib.get(b"__custard_unloaded_task_contents__")?;
+			let unloaded_task_contents = lib
+				.get(b"__custard_unloaded_task_contents__")
+				.map_err(library_error)?;
 			info!("Got unloaded task getter.");
 
+			let version = lib.get(b"__custard_version__").ok();
+
 			ret.symbols = Some(CoreLibrarySymbols {
 				composition,
 				unloaded_datachunk_contents,
 				unloaded_task_contents,
+				version,
 			});
 
 			info!("Loaded library symbols.");
@@ -124,6 +144,24 @@ impl<'lib> SafeLibrary for CoreLibrary<'lib> {
 	}
 }
 
+impl<'lib> CoreLibrary<'lib> {
+	/// The version embedded in the library through `__custard_version__`, if it exports one that parses.
+	pub fn embedded_version(&self) -> Option<Version> {
+		let version_fn = self.symbols.as_ref().unwrap().version.as_ref()?;
+		let version = version_fn();
+		match Version::parse(version.as_str()) {
+			Ok(v) => Some(v),
+			Err(e) => {
+				warn!(
+					"Crate {:?} embeds an invalid version {:?}: {}",
+					self.name, version, e
+				);
+				None
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::error::Error;
@@ -131,7 +169,7 @@ mod tests {
 	use crate::dylib_management::safe_library::{
 		core_library::{
 			CompositionFunctionType, UnloadedDatachunkContentsFunctionType,
-			UnloadedTaskContentsFunctionType,
+			UnloadedTaskContentsFunctionType, VersionFunctionType,
 		},
 		load_types::FFIResult,
 	};
@@ -159,6 +197,12 @@ mod tests {
 		unreachable!()
 	}
 
+	#[no_mangle]
+	#[deny(improper_ctypes_definitions)]
+	pub extern "C" fn ensure_version_ffi_safe() -> Box<String> {
+		unreachable!()
+	}
+
 	#[allow(unused)]
 	const CHECK_COMPOSITION: CompositionFunctionType =
 		ensure_composition_ffi_safe;
@@ -171,4 +215,7 @@ mod tests {
 	#[allow(unused)]
 	const CHECK_UNLOADED_TASK_CONTENTS: UnloadedTaskContentsFunctionType =
 		ensure_unloaded_task_contents_ffi_safe;
+
+	#[allow(unused)]
+	const CHECK_VERSION: VersionFunctionType = ensure_version_ffi_safe;
 }
@@ -50,7 +50,7 @@ mod tests {
 		identify::task_name::FullTaskName,
 		user_types::{
 			datachunk::{DatachunkObject, Datachunkable},
-			task::{TaskClosureType, TaskObject, Taskable},
+			task::{TaskClosure, TaskObject, Taskable},
 			task_control_flow::task_control_flow::{
 				TaskControlFlow, TaskHandlerState,
 			},
@@ -82,7 +82,7 @@ mod tests {
 			&mut self,
 			_: FullTaskName,
 			_: Arc<DatachunkGetter>,
-		) -> TaskClosureType {
+		) -> TaskClosure {
 			unimplemented!();
 		}
 		fn handle_control_flow_update(
@@ -1,5 +1,6 @@
 use crate::{
-	dylib_management::runtime_compile,
+	dylib_management::{fingerprint, runtime_compile},
+	errors::custard_error::CustardError,
 	identify::{crate_name::CrateName, custard_name::CustardName},
 };
 
@@ -7,7 +8,10 @@ use libloading::Library;
 use log::info;
 
 use std::{
-	cell::RefCell, collections::BTreeMap, error::Error, fmt, fs, path::Path,
+	cell::RefCell,
+	collections::{BTreeMap, BTreeSet},
+	fmt, fs,
+	path::Path,
 	rc::Rc,
 };
 
@@ -16,6 +20,8 @@ pub enum LibraryRecompile {
 	Recompile,
 	TryCached,
 	InsistCached,
+	/// Rebuild a crate only if its sources, manifest, lock file or profile changed since the cached library was built.
+	Fingerprint,
 }
 
 #[derive(Clone)]
@@ -30,7 +36,7 @@ pub trait SafeLibrary: fmt::Debug + LibraryDrop {
 		recompile: LibraryRecompile,
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<Library>>>,
-	) -> Result<Self, Box<dyn Error>>
+	) -> Result<Self, CustardError>
 	where
 		Self: Sized;
 
@@ -69,16 +75,75 @@ pub unsafe fn increment_library_usage(name: CrateName) -> u32 {
 	}
 }
 
+/// The file name of a crate's library, for example `libfoo_bar.so` for `foo-bar`.
+pub fn library_file_name(name: &CrateName) -> String {
+	libloading::library_filename(name.get())
+		.to_str()
+		.unwrap()
+		.to_owned()
+		.replace("-", "_")
+}
+
+/// Whether `recompile` asks for `name` to be built before its next copy is loaded.
+fn needs_compile(name: &CrateName, recompile: &LibraryRecompile) -> bool {
+	match recompile {
+		LibraryRecompile::Recompile => true,
+		LibraryRecompile::TryCached => {
+			let usage = unsafe {
+				LIBRARY_USAGE
+					.as_ref()
+					.and_then(|usage| usage.get(name).copied())
+					.unwrap_or(0)
+			};
+			!Path::exists(Path::new(&format!(
+				"custard_dylib_cache/{}{}",
+				library_file_name(name),
+				usage
+			)))
+		}
+		_ => false,
+	}
+}
+
+/// Build every crate in `names` that `recompile` asks to be built, in one cargo invocation. Returns the crates that were built; load them with [LibraryRecompile::InsistCached] so that they are not built again.
+pub fn compile_libraries<'a>(
+	names: impl IntoIterator<Item = &'a CrateName>,
+	recompile: &LibraryRecompile,
+	debug: DebugMode,
+) -> Result<BTreeSet<CrateName>, CustardError> {
+	let mut fingerprints = BTreeMap::new();
+	let to_compile: BTreeSet<CrateName> =
+		if let LibraryRecompile::Fingerprint = recompile {
+			fingerprints = fingerprint::changed(names, &debug)?;
+			fingerprints.keys().cloned().collect()
+		} else {
+			names
+				.into_iter()
+				.filter(|name| needs_compile(name, recompile))
+				.cloned()
+				.collect()
+		};
+	if !to_compile.is_empty() {
+		info!("Compiling crates: {:?}", to_compile);
+		runtime_compile::compile(
+			&to_compile.iter().cloned().collect::<Vec<_>>(),
+			debug,
+		)?;
+	}
+	for (name, fingerprint) in &fingerprints {
+		fingerprint::store(name, *fingerprint)?;
+	}
+	Ok(to_compile)
+}
+
 pub fn load_crate_as_library(
 	name: CrateName,
 	recompile: LibraryRecompile,
 	debug: DebugMode,
-) -> Result<libloading::Library, Box<dyn Error>> {
-	let library_name = libloading::library_filename(name.get())
-		.to_str()
-		.unwrap()
-		.to_owned()
-		.replace("-", "_");
+) -> Result<(libloading::Library, String), CustardError> {
+	let library_name = library_file_name(&name);
+
+	compile_libraries(std::iter::once(&name), &recompile, debug)?;
 
 	let usage = unsafe { increment_library_usage(name.clone()) };
 
@@ -86,22 +151,21 @@ pub fn load_crate_as_library(
 
 	let path = format!("{}{}", old_path, usage);
 
-	let should_recompile = match recompile {
-		LibraryRecompile::Recompile => true,
-		LibraryRecompile::TryCached => !Path::exists(Path::new(&path)),
-		_ => false,
+	let library_error = |e: Box<dyn std::error::Error>| CustardError::Library {
+		crate_name: name.clone(),
+		source: e,
 	};
 
-	if should_recompile {
-		runtime_compile::compile(name.clone(), library_name.as_str(), debug)?;
-	}
-
-	if fs::try_exists(&old_path)? {
-		fs::copy(old_path, &path)?;
+	if fs::try_exists(&old_path).map_err(|e| library_error(Box::new(e)))? {
+		fs::copy(old_path, &path).map_err(|e| library_error(Box::new(e)))?;
 	}
 
 	info!("Instantiating new library: {}", path);
-	let ret = Ok(unsafe { libloading::Library::new(path.clone()) }?);
+	let ret = Ok((
+		unsafe { libloading::Library::new(path.clone()) }
+			.map_err(|e| library_error(Box::new(e)))?,
+		path.clone(),
+	));
 	info!("Instantiated library: {}", path);
 
 	ret
@@ -5,9 +5,12 @@ use crate::{
 			self, DebugMode, LibraryDrop, LibraryRecompile, SafeLibrary,
 		},
 	},
-	errors::load_errors::{
-		custard_load_datachunk_error::CustardLoadDatachunkError,
-		custard_load_task_error::CustardLoadTaskError,
+	errors::{
+		custard_error::CustardError,
+		load_errors::{
+			custard_load_datachunk_error::CustardLoadDatachunkError,
+			custard_load_task_error::CustardLoadTaskError,
+		},
 	},
 	identify::crate_name::CrateName,
 	user_types::{datachunk::DatachunkObject, task::TaskObject},
@@ -15,12 +18,13 @@ use crate::{
 
 use libloading::Symbol;
 
-use std::{cell::RefCell, error::Error, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 #[derive(Debug)]
 pub struct UserLibrary {
 	name: CrateName,
 	lib: Option<libloading::Library>,
+	path: String,
 	drop_list: Rc<RefCell<Vec<libloading::Library>>>,
 }
 
@@ -42,15 +46,16 @@ impl SafeLibrary for UserLibrary {
 		recompile: LibraryRecompile,
 		debug: DebugMode,
 		drop_list: Rc<RefCell<Vec<libloading::Library>>>,
-	) -> Result<Self, Box<dyn Error>> {
-		let lib = Some(safe_library::load_crate_as_library(
+	) -> Result<Self, CustardError> {
+		let (lib, path) = safe_library::load_crate_as_library(
 			name.clone(),
 			recompile,
 			debug,
-		)?);
+		)?;
 		Ok(Self {
 			name,
-			lib,
+			lib: Some(lib),
+			path,
 			drop_list,
 		})
 	}
@@ -71,11 +76,16 @@ impl SafeLibrary for UserLibrary {
 }
 
 impl UserLibrary {
+	/// The path of the copy of the library in `custard_dylib_cache` that this instance loaded.
+	pub fn get_library_path(&self) -> &str {
+		self.path.as_str()
+	}
+
 	pub fn load_datachunk(
 		&self,
 		type_name: &str,
 		deserialize_str: &str,
-	) -> Result<DatachunkObject, Box<dyn Error>> {
+	) -> Result<DatachunkObject, CustardError> {
 		let load_fn: Symbol<DatachunkLoadFn> = match unsafe {
 			self.lib
 				.as_ref()
@@ -84,11 +94,12 @@ impl UserLibrary {
 		} {
 			Ok(v) => v,
 			Err(e) => {
-				return Err(Box::new(CustardLoadDatachunkError {
+				return Err(CustardLoadDatachunkError {
 					crate_name: self.name.clone(),
 					type_name: type_name.to_owned(),
 					wrapped_error: Box::new(e),
-				}))
+				}
+				.into())
 			}
 		};
 
@@ -101,20 +112,34 @@ impl UserLibrary {
 				return Ok(v);
 			}
 			FFIResult::Err(e) => {
-				return Err(Box::new(CustardLoadDatachunkError {
+				return Err(CustardLoadDatachunkError {
 					crate_name: self.name.clone(),
 					type_name: type_name.to_owned(),
 					wrapped_error: e,
-				}));
+				}
+				.into());
 			}
 		};
 	}
 
+	/// Whether this library exports a loader for the task type `type_name`.
+	pub fn has_task_type(&self, type_name: &str) -> bool {
+		unsafe {
+			self.lib
+				.as_ref()
+				.unwrap()
+				.get::<TaskLoadFn>(
+					format!("__custard_task__{}", type_name).as_bytes(),
+				)
+				.is_ok()
+		}
+	}
+
 	pub fn load_task(
 		&self,
 		type_name: &str,
 		deserialize_str: &str,
-	) -> Result<TaskObject, Box<dyn Error>> {
+	) -> Result<TaskObject, CustardError> {
 		let load_fn: Symbol<TaskLoadFn> = match unsafe {
 			self.lib
 				.as_ref()
@@ -123,11 +148,12 @@ impl UserLibrary {
 		} {
 			Ok(v) => v,
 			Err(e) => {
-				return Err(Box::new(CustardLoadTaskError {
+				return Err(CustardLoadTaskError {
 					crate_name: self.name.clone(),
 					type_name: type_name.to_owned(),
 					wrapped_error: Box::new(e),
-				}))
+				}
+				.into())
 			}
 		};
 
@@ -139,11 +165,12 @@ impl UserLibrary {
 				return Ok(v);
 			}
 			FFIResult::Err(e) => {
-				return Err(Box::new(CustardLoadTaskError {
+				return Err(CustardLoadTaskError {
 					crate_name: self.name.clone(),
 					type_name: type_name.to_owned(),
 					wrapped_error: e,
-				}));
+				}
+				.into());
 			}
 		};
 	}
@@ -0,0 +1,240 @@
+use crate::{
+	errors::{
+		datachunk_errors::{
+			custard_datachunk_access_error::CustardDatachunkAccessError,
+			custard_datachunk_not_found_error::CustardDatachunkNotFoundError,
+			custard_datachunk_not_partitionable_error::CustardDatachunkNotPartitionableError,
+			custard_datachunk_type_mismatch_error::CustardDatachunkTypeMismatchError,
+			custard_partition_not_found_error::CustardPartitionNotFoundError,
+		},
+		load_errors::{
+			custard_composition_requires_core_crate_error::CustardCompositionRequiresCoreCrateError,
+			custard_crate_version_mismatch_error::CustardCrateVersionMismatchError,
+			custard_load_datachunk_error::CustardLoadDatachunkError,
+			custard_load_task_error::CustardLoadTaskError,
+		},
+		parse_errors::{
+			custard_composition_cycle_error::CustardCompositionCycleError,
+			custard_ron_parse_error::CustardRonCompositionParseError,
+		},
+		run_errors::custard_task_panic_error::CustardTaskPanicError,
+		task_composition_errors::{
+			custard_crate_not_found_error::CustardCrateNotFoundError,
+			custard_disabled_task_writes_error::CustardDisabledTaskWritesError,
+			custard_not_in_cycle_error::CustardNotInCycleError,
+			custard_parent_not_found_error::CustardParentNotFoundError,
+			custard_task_already_exists_error::CustardTaskAlreadyExistsError,
+			custard_task_not_found_error::CustardTaskNotFoundError,
+			custard_task_still_required_error::CustardTaskStillRequiredError,
+			custard_task_type_not_loaded_error::CustardTaskTypeNotLoadedError,
+			custard_unreachable_task_error::CustardUnreachableTaskError,
+		},
+	},
+	identify::crate_name::CrateName,
+};
+
+use thiserror::Error;
+
+use std::error::Error;
+
+/// Broad category of a [CustardError], for callers that only need to know which stage failed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CustardErrorKind {
+	Parse,
+	Load,
+	Composition,
+	Datachunk,
+	Run,
+}
+
+/// Every error returned by composition parsing, checking and loading, and by the libraries. Each variant has a stable [code](CustardError::code) and keeps the error it wraps as its source, so the variant's structured fields can be matched on without downcasting. A variant's message only says what went wrong; the details are in its source, so use [report](crate::utils::error_report::report) to show the whole chain.
+#[derive(Debug, Error)]
+pub enum CustardError {
+	#[error("[P001] Could not parse composition")]
+	RonParse(#[source] Box<CustardRonCompositionParseError>),
+	#[error("[P002] Compositions include each other as children")]
+	CompositionCycle(#[source] Box<CustardCompositionCycleError>),
+	#[error("[P003] Could not write composition as RON")]
+	RonSerialize(#[source] Box<ron::Error>),
+
+	#[error("[L001] Crate has no core library")]
+	CompositionRequiresCoreCrate(
+		#[source] Box<CustardCompositionRequiresCoreCrateError>,
+	),
+	#[error("[L002] Could not load datachunk")]
+	LoadDatachunk(#[source] Box<CustardLoadDatachunkError>),
+	#[error("[L003] Could not load task")]
+	LoadTask(#[source] Box<CustardLoadTaskError>),
+	/// `crate_names` are the crates that were being built together, as a failed batch does not say which of them was at fault.
+	#[error("[L004] Could not compile crates {crate_names:?}")]
+	Compile {
+		crate_names: Vec<CrateName>,
+		source: Box<dyn Error>,
+	},
+	#[error("[L005] Could not load library for crate {crate_name:?}")]
+	Library {
+		crate_name: CrateName,
+		source: Box<dyn Error>,
+	},
+	#[error("[L006] {function} in crate {crate_name:?} failed")]
+	CoreLibraryFunction {
+		crate_name: CrateName,
+		function: &'static str,
+		source: Box<dyn Error>,
+	},
+	#[error("[L007] Crate version does not satisfy its requirement")]
+	CrateVersionMismatch(#[source] Box<CustardCrateVersionMismatchError>),
+	#[error("[L008] Could not read workspace metadata")]
+	Metadata { source: Box<dyn Error> },
+
+	#[error("[C001] Task is not in a cycle")]
+	NotInCycle(#[source] Box<CustardNotInCycleError>),
+	#[error("[C002] Task cannot be reached from an entrypoint")]
+	UnreachableTask(#[source] Box<CustardUnreachableTaskError>),
+	#[error("[C003] Task already exists")]
+	TaskAlreadyExists(#[source] Box<CustardTaskAlreadyExistsError>),
+	#[error("[C004] Task not found")]
+	TaskNotFound(#[source] Box<CustardTaskNotFoundError>),
+	#[error("[C005] Task is still a parent of another task")]
+	TaskStillRequired(#[source] Box<CustardTaskStillRequiredError>),
+	#[error("[C006] Task type is not loaded")]
+	TaskTypeNotLoaded(#[source] Box<CustardTaskTypeNotLoadedError>),
+	#[error("[C007] Parent not found")]
+	ParentNotFound(#[source] Box<CustardParentNotFoundError>),
+	#[error("[C008] Crate not found")]
+	CrateNotFound(#[source] Box<CustardCrateNotFoundError>),
+	#[error("[C009] Task disabled by a cycle hook writes a datachunk the hook does not provide")]
+	DisabledTaskWrites(#[source] Box<CustardDisabledTaskWritesError>),
+
+	#[error("[D001] Unsynchronized tasks access a datachunk in conflict")]
+	DatachunkAccess(#[source] Box<CustardDatachunkAccessError>),
+	#[error("[D002] Datachunk type mismatch")]
+	DatachunkTypeMismatch(#[source] Box<CustardDatachunkTypeMismatchError>),
+	#[error("[D003] Datachunk not found")]
+	DatachunkNotFound(#[source] Box<CustardDatachunkNotFoundError>),
+	#[error("[D004] Partition not found")]
+	PartitionNotFound(#[source] Box<CustardPartitionNotFoundError>),
+	#[error("[D005] Datachunk type is not partitionable")]
+	DatachunkNotPartitionable(
+		#[source] Box<CustardDatachunkNotPartitionableError>,
+	),
+
+	#[error("[R001] Task panicked")]
+	TaskPanic(#[source] Box<CustardTaskPanicError>),
+	#[error("[R002] Could not access {path}")]
+	File {
+		path: String,
+		source: Box<dyn Error>,
+	},
+	#[error("[R003] Could not restore {name} from checkpoint")]
+	Restore {
+		name: String,
+		source: Box<dyn Error + Send + Sync>,
+	},
+}
+
+impl CustardError {
+	/// A short code that identifies the variant and will not change between releases.
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::RonParse(_) => "P001",
+			Self::CompositionCycle(_) => "P002",
+			Self::RonSerialize(_) => "P003",
+			Self::CompositionRequiresCoreCrate(_) => "L001",
+			Self::LoadDatachunk(_) => "L002",
+			Self::LoadTask(_) => "L003",
+			Self::Compile { .. } => "L004",
+			Self::Library { .. } => "L005",
+			Self::CoreLibraryFunction { .. } => "L006",
+			Self::CrateVersionMismatch(_) => "L007",
+			Self::Metadata { .. } => "L008",
+			Self::NotInCycle(_) => "C001",
+			Self::UnreachableTask(_) => "C002",
+			Self::TaskAlreadyExists(_) => "C003",
+			Self::TaskNotFound(_) => "C004",
+			Self::TaskStillRequired(_) => "C005",
+			Self::TaskTypeNotLoaded(_) => "C006",
+			Self::ParentNotFound(_) => "C007",
+			Self::CrateNotFound(_) => "C008",
+			Self::DisabledTaskWrites(_) => "C009",
+			Self::DatachunkAccess(_) => "D001",
+			Self::DatachunkTypeMismatch(_) => "D002",
+			Self::DatachunkNotFound(_) => "D003",
+			Self::PartitionNotFound(_) => "D004",
+			Self::DatachunkNotPartitionable(_) => "D005",
+			Self::TaskPanic(_) => "R001",
+			Self::File { .. } => "R002",
+			Self::Restore { .. } => "R003",
+		}
+	}
+
+	pub fn kind(&self) -> CustardErrorKind {
+		match self {
+			Self::RonParse(_)
+			| Self::CompositionCycle(_)
+			| Self::RonSerialize(_) => CustardErrorKind::Parse,
+			Self::CompositionRequiresCoreCrate(_)
+			| Self::LoadDatachunk(_)
+			| Self::LoadTask(_)
+			| Self::Compile { .. }
+			| Self::Library { .. }
+			| Self::CoreLibraryFunction { .. }
+			| Self::CrateVersionMismatch(_)
+			| Self::Metadata { .. } => CustardErrorKind::Load,
+			Self::NotInCycle(_)
+			| Self::UnreachableTask(_)
+			| Self::TaskAlreadyExists(_)
+			| Self::TaskNotFound(_)
+			| Self::TaskStillRequired(_)
+			| Self::TaskTypeNotLoaded(_)
+			| Self::ParentNotFound(_)
+			| Self::CrateNotFound(_)
+			| Self::DisabledTaskWrites(_) => CustardErrorKind::Composition,
+			Self::DatachunkAccess(_)
+			| Self::DatachunkTypeMismatch(_)
+			| Self::DatachunkNotFound(_)
+			| Self::PartitionNotFound(_)
+			| Self::DatachunkNotPartitionable(_) => CustardErrorKind::Datachunk,
+			Self::TaskPanic(_) | Self::File { .. } | Self::Restore { .. } => {
+				CustardErrorKind::Run
+			}
+		}
+	}
+}
+
+/// The wrapped errors are boxed to keep `CustardError` small, so they are converted by hand rather than with `#[from]`.
+macro_rules! boxed_from {
+	($($error:ty => $variant:ident),* $(,)?) => {
+		$(
+			impl From<$error> for CustardError {
+				fn from(error: $error) -> Self {
+					Self::$variant(Box::new(error))
+				}
+			}
+		)*
+	};
+}
+
+boxed_from!(
+	CustardRonCompositionParseError => RonParse,
+	CustardCompositionCycleError => CompositionCycle,
+	CustardCompositionRequiresCoreCrateError => CompositionRequiresCoreCrate,
+	CustardLoadDatachunkError => LoadDatachunk,
+	CustardLoadTaskError => LoadTask,
+	CustardCrateVersionMismatchError => CrateVersionMismatch,
+	CustardNotInCycleError => NotInCycle,
+	CustardUnreachableTaskError => UnreachableTask,
+	CustardTaskAlreadyExistsError => TaskAlreadyExists,
+	CustardTaskNotFoundError => TaskNotFound,
+	CustardTaskStillRequiredError => TaskStillRequired,
+	CustardTaskTypeNotLoadedError => TaskTypeNotLoaded,
+	CustardParentNotFoundError => ParentNotFound,
+	CustardCrateNotFoundError => CrateNotFound,
+	CustardDisabledTaskWritesError => DisabledTaskWrites,
+	CustardDatachunkAccessError => DatachunkAccess,
+	CustardDatachunkTypeMismatchError => DatachunkTypeMismatch,
+	CustardDatachunkNotFoundError => DatachunkNotFound,
+	CustardPartitionNotFoundError => PartitionNotFound,
+	CustardDatachunkNotPartitionableError => DatachunkNotPartitionable,
+	CustardTaskPanicError => TaskPanic,
+);
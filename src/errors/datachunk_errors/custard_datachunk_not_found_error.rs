@@ -0,0 +1,17 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::{
+	datachunk_name::FullDatachunkName, task_name::FullTaskName,
+};
+
+#[derive(Debug, Error)]
+/// A task accesses a datachunk, or a datachunk of a crate, that is not in the composition.
+pub struct CustardDatachunkNotFoundError {
+	pub offending_datachunk: FullDatachunkName,
+	pub accessed_by: FullTaskName,
+	/// The datachunk in the composition whose name is closest, if it is close enough to be a likely typo.
+	pub suggestion: Option<FullDatachunkName>,
+}
+
+display_from_debug!(CustardDatachunkNotFoundError);
@@ -0,0 +1,13 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::datachunk_name::FullDatachunkName;
+
+#[derive(Debug, Error)]
+/// A datachunk lists `partitions` in its composition, but its type does not report being [Partitionable](crate::user_types::datachunk::Partitionable) through [is_partitionable](crate::user_types::datachunk::Datachunkable::is_partitionable).
+pub struct CustardDatachunkNotPartitionableError {
+	pub datachunk: FullDatachunkName,
+	pub type_name: String,
+}
+
+display_from_debug!(CustardDatachunkNotPartitionableError);
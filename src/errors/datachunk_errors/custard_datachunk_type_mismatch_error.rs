@@ -0,0 +1,17 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::{
+	datachunk_name::FullDatachunkName, task_name::FullTaskName,
+};
+
+#[derive(Debug, Error)]
+/// A datachunk's type does not match what was expected of it. `expected_by` is the task whose access stated the type, or `None` if the composition's `type_name` disagrees with the type the library actually loaded.
+pub struct CustardDatachunkTypeMismatchError {
+	pub datachunk: FullDatachunkName,
+	pub expected_by: Option<FullTaskName>,
+	pub expected_type: String,
+	pub found_type: String,
+}
+
+display_from_debug!(CustardDatachunkTypeMismatchError);
@@ -0,0 +1,18 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::{
+	datachunk_name::FullDatachunkName, task_name::FullTaskName,
+};
+
+#[derive(Debug, Error)]
+/// A task accesses a partition that its datachunk does not declare.
+pub struct CustardPartitionNotFoundError {
+	pub datachunk: FullDatachunkName,
+	pub offending_partition: String,
+	pub accessed_by: FullTaskName,
+	/// The declared partition whose name is closest, if it is close enough to be a likely typo.
+	pub suggestion: Option<String>,
+}
+
+display_from_debug!(CustardPartitionNotFoundError);
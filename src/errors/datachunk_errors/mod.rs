@@ -1 +1,5 @@
 pub mod custard_datachunk_access_error;
+pub mod custard_datachunk_not_found_error;
+pub mod custard_datachunk_not_partitionable_error;
+pub mod custard_datachunk_type_mismatch_error;
+pub mod custard_partition_not_found_error;
@@ -0,0 +1,58 @@
+use cargo_metadata::{Version, VersionReq};
+use thiserror::Error;
+
+use crate::identify::{crate_name::CrateName, custard_name::CustardName};
+
+use std::fmt::{self, Display, Formatter};
+
+/// Where the version checked against a requirement came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CrateVersionOrigin {
+	/// The package version in the crate's `Cargo.toml`, as reported by `cargo metadata`.
+	Manifest,
+	/// The version embedded in the loaded core library.
+	Library,
+}
+
+/// A child crate does not satisfy the version requirement declared for it in `child_versions`.
+#[derive(Debug, Error)]
+pub struct CustardCrateVersionMismatchError {
+	pub offending_crate: CrateName,
+	/// The crate whose composition declared the requirement, or `None` for the root composition.
+	pub required_by: Option<CrateName>,
+	pub required: VersionReq,
+	pub found_in: CrateVersionOrigin,
+	/// `None` if no version could be found at all.
+	pub found: Option<Version>,
+}
+
+impl Display for CustardCrateVersionMismatchError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		let required_by = match &self.required_by {
+			Some(v) => format!("crate `{}`", v.get()),
+			None => "the root composition".to_owned(),
+		};
+		write!(
+			f,
+			"{} requires crate `{}` at version {}, but ",
+			required_by,
+			self.offending_crate.get(),
+			self.required
+		)?;
+		match (self.found_in, &self.found) {
+			(CrateVersionOrigin::Manifest, Some(v)) => {
+				write!(f, "its Cargo.toml declares version {}", v)
+			}
+			(CrateVersionOrigin::Manifest, None) => {
+				write!(f, "it is not a package in this workspace")
+			}
+			(CrateVersionOrigin::Library, Some(v)) => {
+				write!(f, "the loaded library was built as version {}", v)
+			}
+			(CrateVersionOrigin::Library, None) => write!(
+				f,
+				"the loaded library does not export __custard_version__"
+			),
+		}
+	}
+}
@@ -9,6 +9,7 @@ use thiserror;
 pub struct CustardLoadDatachunkError {
 	pub crate_name: CrateName,
 	pub type_name: String,
+	#[source]
 	pub wrapped_error: Box<dyn Error>,
 }
 
@@ -8,6 +8,7 @@ use std::error::Error;
 pub struct CustardLoadTaskError {
 	pub crate_name: CrateName,
 	pub type_name: String,
+	#[source]
 	pub wrapped_error: Box<dyn Error>,
 }
 
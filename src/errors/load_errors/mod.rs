@@ -1,4 +1,5 @@
 pub mod custard_composition_requires_core_crate_error;
+pub mod custard_crate_version_mismatch_error;
 pub mod custard_load_datachunk_error;
 pub mod custard_load_task_error;
 pub mod custard_unloaded_static_array_does_not_contain_element_error;
@@ -1,3 +1,5 @@
+pub mod custard_error;
+
 pub mod datachunk_errors;
 pub mod load_errors;
 pub mod parse_errors;
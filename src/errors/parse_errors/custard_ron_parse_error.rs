@@ -1,11 +1,203 @@
-use custard_macros::display_from_debug;
-use ron::Error;
+use ron::{error::ErrorCode, Error};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+use crate::{
+	identify::{crate_name::CrateName, custard_name::CustardName},
+	utils::edit_distance::closest_match,
+};
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// Lines of RON shown on either side of the offending line.
+const SNIPPET_CONTEXT_LINES: usize = 1;
+
+#[derive(Error)]
 pub struct CustardRonCompositionParseError {
+	#[source]
 	pub error: Error,
+	/// The core crate whose composition failed to parse, or `None` for the root composition.
+	pub origin: Option<CrateName>,
 	pub relevant_ron: String,
 }
 
-display_from_debug!(CustardRonCompositionParseError);
+impl CustardRonCompositionParseError {
+	/// The 1-based line and column of the error, when RON reports one. Serde errors such as unknown fields carry no position; for those only the [hint](Self::hint) names the offending identifier.
+	pub fn position(&self) -> Option<(usize, usize)> {
+		let position = &self.error.position;
+		if position.line == 0 {
+			return None;
+		}
+		Some((position.line, position.col))
+	}
+
+	/// A suggestion for common mistakes, such as misspelled fields of `UnloadedTask` or variants of `AccessType`.
+	pub fn hint(&self) -> Option<String> {
+		let message = match &self.error.code {
+			ErrorCode::Message(v) => v,
+			_ => return None,
+		};
+		let identifier = self.offending_identifier()?;
+		let expected = Self::backticked(
+			message.split_once("expected").map_or("", |(_, v)| v),
+		);
+
+		if message.starts_with("unknown field")
+			|| message.starts_with("unknown variant")
+		{
+			let kind = if message.starts_with("unknown field") {
+				"field"
+			} else {
+				"variant"
+			};
+			return Some(
+				match closest_match(identifier, expected.iter().copied()) {
+					Some(v) => format!(
+						"unknown {} `{}`; did you mean `{}`?",
+						kind, identifier, v
+					),
+					None => format!(
+						"unknown {} `{}`; expected one of {}",
+						kind,
+						identifier,
+						expected
+							.iter()
+							.map(|v| format!("`{}`", v))
+							.collect::<Vec<_>>()
+							.join(", ")
+					),
+				},
+			);
+		}
+		if message.starts_with("missing field") {
+			return Some(format!("`{}` is required here", identifier));
+		}
+		None
+	}
+
+	fn offending_identifier(&self) -> Option<&str> {
+		match &self.error.code {
+			ErrorCode::Message(v) => Self::backticked(v).into_iter().next(),
+			_ => None,
+		}
+	}
+
+	fn backticked(text: &str) -> Vec<&str> {
+		text.split('`').skip(1).step_by(2).collect()
+	}
+
+	fn fmt_snippet(
+		&self,
+		f: &mut Formatter,
+		line: usize,
+		col: usize,
+	) -> fmt::Result {
+		let lines: Vec<&str> = self.relevant_ron.lines().collect();
+		let first = line.saturating_sub(SNIPPET_CONTEXT_LINES).max(1);
+		let last = (line + SNIPPET_CONTEXT_LINES).min(lines.len());
+		let width = last.to_string().len();
+
+		for line_n in first..=last {
+			let text = lines[line_n - 1];
+			writeln!(f, "{:>width$} | {}", line_n, text, width = width)?;
+			if line_n == line {
+				//keep tabs so that the caret lines up with the source
+				let padding: String = text
+					.chars()
+					.take(col.saturating_sub(1))
+					.map(|c| if c == '\t' { '\t' } else { ' ' })
+					.collect();
+				writeln!(f, "{:>width$} | {}^", "", padding, width = width)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Display for CustardRonCompositionParseError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		let origin = match &self.origin {
+			Some(v) => format!("composition of crate `{}`", v.get()),
+			None => "root composition".to_owned(),
+		};
+		let position = self.position();
+		match position {
+			Some((line, col)) => writeln!(
+				f,
+				"Could not parse {} at {}:{}: {}",
+				origin, line, col, self.error.code
+			)?,
+			None => {
+				writeln!(f, "Could not parse {}: {}", origin, self.error.code)?
+			}
+		}
+		if let Some((line, col)) = position {
+			if line <= self.relevant_ron.lines().count() {
+				self.fmt_snippet(f, line, col)?;
+			}
+		}
+		if let Some(hint) = self.hint() {
+			write!(f, "hint: {}", hint)?;
+		}
+		Ok(())
+	}
+}
+
+impl Debug for CustardRonCompositionParseError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("CustardRonCompositionParseError")
+			.field("error", &self.error)
+			.field("origin", &self.origin)
+			.field("position", &self.position())
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::CustardRonCompositionParseError;
+
+	use crate::composition::unloaded::unloaded_task::UnloadedTask;
+
+	fn parse_task_error(ron: &str) -> CustardRonCompositionParseError {
+		CustardRonCompositionParseError {
+			error: ron::from_str::<UnloadedTask>(ron).unwrap_err(),
+			origin: None,
+			relevant_ron: ron.to_owned(),
+		}
+	}
+
+	#[test]
+	fn misspelled_field_is_hinted_without_guessing_a_position() {
+		let error = parse_task_error(
+			"(\n\ttype_name: \"A\",\n\tdeserialize_path: \"a.ron\",\n\tparent: [],\n)",
+		);
+		assert_eq!(error.position(), None);
+		assert_eq!(
+			error.hint().unwrap(),
+			"unknown field `parent`; did you mean `parents`?"
+		);
+		assert!(!error.to_string().contains('^'));
+	}
+
+	#[test]
+	fn syntax_error_is_located() {
+		let error = parse_task_error(
+			"(\n\ttype_name: \"A\",\n\tdeserialize_path: a.ron,\n)",
+		);
+		assert_eq!(error.position(), Some((3, 20)));
+		assert!(error.to_string().contains(
+			"3 | \tdeserialize_path: a.ron,\n  | \t                  ^"
+		));
+	}
+
+	#[test]
+	fn misspelled_access_type_is_hinted() {
+		let error = parse_task_error(
+			"(type_name: \"A\", deserialize_path: \"a.ron\", parents: [], accesses: [(of: (crate_name: (name: \"c\"), datachunk_name: (name: \"d\")), mut_immut: MutableAcess)], entrypoint: true)",
+		);
+		assert_eq!(
+			error.hint().unwrap(),
+			"unknown variant `MutableAcess`; did you mean `MutableAccess`?"
+		);
+	}
+}
@@ -1,14 +1,77 @@
-use crate::identify::task_name::FullTaskName;
-
-use custard_macros::display_from_debug;
+use crate::{
+	identify::{custard_name::CustardName, task_name::FullTaskName},
+	utils::panic_capture::{self, PanicLocation},
+};
 
 use thiserror::Error;
 
-use std::any::Any;
+use std::{
+	any::Any,
+	backtrace::Backtrace,
+	fmt::{self, Display, Formatter},
+};
 
 #[derive(Debug, Error)]
 pub struct CustardTaskPanicError {
 	pub offending_task: FullTaskName,
+	/// The panic message, if the payload was a string.
+	pub message: Option<String>,
+	pub location: Option<PanicLocation>,
+	/// The copy of the dynamic library (for example `custard_dylib_cache/libfoo.so2`) that the panicking task was loaded from.
+	pub library_path: String,
+	/// Only shown by the alternate form, `{:#}`.
+	pub backtrace: Option<Backtrace>,
 	pub error: Box<dyn Any + Send>,
 }
-display_from_debug!(CustardTaskPanicError);
+
+impl CustardTaskPanicError {
+	/// Build a panic error from a payload returned by `catch_unwind`, collecting whatever [panic_capture::catch_unwind] recorded on this thread.
+	pub(crate) fn new(
+		offending_task: FullTaskName,
+		library_path: String,
+		error: Box<dyn Any + Send>,
+	) -> Self {
+		let captured = panic_capture::take_captured();
+		let (location, backtrace) = match captured {
+			Some(v) => (v.location, Some(v.backtrace)),
+			None => (None, None),
+		};
+		Self {
+			offending_task,
+			message: panic_capture::payload_message(&*error),
+			location,
+			library_path,
+			backtrace,
+			error,
+		}
+	}
+}
+
+impl Display for CustardTaskPanicError {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(
+			f,
+			"Task {}::{} panicked",
+			self.offending_task.crate_name.get(),
+			self.offending_task.task_name.get()
+		)?;
+		if let Some(location) = &self.location {
+			write!(
+				f,
+				" at {}:{}:{}",
+				location.file, location.line, location.column
+			)?;
+		}
+		write!(f, " (in {})", self.library_path)?;
+		match &self.message {
+			Some(v) => write!(f, ": {}", v)?,
+			None => write!(f, " with a non-string payload")?,
+		}
+		if f.alternate() {
+			if let Some(backtrace) = &self.backtrace {
+				write!(f, "\n{}", backtrace)?;
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,12 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::{crate_name::CrateName, task_name::FullTaskName};
+#[derive(Debug, Error)]
+/// A task edit tried to add a task to a crate that is not in the composition.
+pub struct CustardCrateNotFoundError {
+	pub offending_crate: CrateName,
+	pub required_by: FullTaskName,
+}
+
+display_from_debug!(CustardCrateNotFoundError);
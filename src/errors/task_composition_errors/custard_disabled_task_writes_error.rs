@@ -0,0 +1,16 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::{
+	datachunk_name::FullDatachunkName, task_name::FullTaskName,
+};
+#[derive(Debug, Error)]
+/// A cycle hook disables a task that also writes a datachunk the hook does not provide, so that write would be lost.
+pub struct CustardDisabledTaskWritesError {
+	pub disabled_task: FullTaskName,
+	/// The boundary of the hook that disables the task.
+	pub boundary: FullTaskName,
+	pub unprovided: FullDatachunkName,
+}
+
+display_from_debug!(CustardDisabledTaskWritesError);
@@ -0,0 +1,14 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::task_name::FullTaskName;
+#[derive(Debug, Error)]
+/// A task lists a parent that is not in the composition.
+pub struct CustardParentNotFoundError {
+	pub offending_task: FullTaskName,
+	pub required_by: FullTaskName,
+	/// The task in the composition whose name is closest, if it is close enough to be a likely typo.
+	pub suggestion: Option<FullTaskName>,
+}
+
+display_from_debug!(CustardParentNotFoundError);
@@ -0,0 +1,11 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::task_name::FullTaskName;
+#[derive(Debug, Error)]
+/// A task edit tried to add a task under a name that is already in the composition.
+pub struct CustardTaskAlreadyExistsError {
+	pub offending_task: FullTaskName,
+}
+
+display_from_debug!(CustardTaskAlreadyExistsError);
@@ -0,0 +1,11 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::task_name::FullTaskName;
+#[derive(Debug, Error)]
+/// A task edit referred to a task that is not in the composition.
+pub struct CustardTaskNotFoundError {
+	pub offending_task: FullTaskName,
+}
+
+display_from_debug!(CustardTaskNotFoundError);
@@ -0,0 +1,12 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::task_name::FullTaskName;
+#[derive(Debug, Error)]
+/// A task edit tried to remove a task that a remaining task still lists as a parent.
+pub struct CustardTaskStillRequiredError {
+	pub offending_task: FullTaskName,
+	pub required_by: FullTaskName,
+}
+
+display_from_debug!(CustardTaskStillRequiredError);
@@ -0,0 +1,12 @@
+use custard_macros::display_from_debug;
+use thiserror::Error;
+
+use crate::identify::task_name::FullTaskName;
+#[derive(Debug, Error)]
+/// A task edit tried to add a task whose type is not exported by its crate's loaded library.
+pub struct CustardTaskTypeNotLoadedError {
+	pub offending_task: FullTaskName,
+	pub type_name: String,
+}
+
+display_from_debug!(CustardTaskTypeNotLoadedError);
@@ -1,2 +1,9 @@
+pub mod custard_crate_not_found_error;
+pub mod custard_disabled_task_writes_error;
 pub mod custard_not_in_cycle_error;
+pub mod custard_parent_not_found_error;
+pub mod custard_task_already_exists_error;
+pub mod custard_task_not_found_error;
+pub mod custard_task_still_required_error;
+pub mod custard_task_type_not_loaded_error;
 pub mod custard_unreachable_task_error;
@@ -1,8 +1,12 @@
 use crate::identify::{crate_name::CrateName, custard_name::CustardName};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+use std::fmt::{self, Display, Formatter};
+
+#[derive(
+	Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize,
+)]
 pub struct FullDatachunkName {
 	pub crate_name: CrateName,
 	pub datachunk_name: DatachunkName,
@@ -17,7 +21,16 @@ impl FullDatachunkName {
 	}
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+/// Written as `crate/name`.
+impl Display for FullDatachunkName {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "{}/{}", self.crate_name.get(), self.datachunk_name.get())
+	}
+}
+
+#[derive(
+	Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize,
+)]
 pub struct DatachunkName {
 	name: String,
 }
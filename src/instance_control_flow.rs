@@ -1,12 +1,83 @@
-use crate::identify::crate_name::CrateName;
+use crate::{
+	composition::unloaded::task_edits::TaskEdits,
+	identify::crate_name::CrateName,
+};
 
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, path::PathBuf, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub enum InstanceControlFlow {
+	/// Save a checkpoint, then do what the second field asks, which is never another checkpoint.
+	Checkpoint(Arc<PathBuf>, Box<InstanceControlFlow>),
 	Continue,
+	EditTasks(Arc<TaskEdits>),
 	FullReload,
 	PartialReload(Arc<BTreeSet<CrateName>>),
 	RecreateThreadpool,
 	Stop,
 }
+
+impl InstanceControlFlow {
+	/// Add a request made by a task to the one already pending. A checkpoint is kept alongside whatever else is requested, and saved first; task edits are combined; otherwise the later request wins.
+	pub(crate) fn merge(&mut self, request: InstanceControlFlow) {
+		let pending = std::mem::replace(self, InstanceControlFlow::Continue);
+		*self = match (pending, request) {
+			(
+				InstanceControlFlow::Checkpoint(_, then),
+				InstanceControlFlow::Checkpoint(path, _),
+			) => InstanceControlFlow::Checkpoint(path, then),
+			(InstanceControlFlow::Checkpoint(path, mut then), request) => {
+				then.merge(request);
+				InstanceControlFlow::Checkpoint(path, then)
+			}
+			(pending, InstanceControlFlow::Checkpoint(path, _)) => {
+				InstanceControlFlow::Checkpoint(path, Box::new(pending))
+			}
+			(
+				InstanceControlFlow::EditTasks(pending),
+				InstanceControlFlow::EditTasks(edits),
+			) => {
+				let mut merged = (*pending).clone();
+				merged.merge(&edits);
+				InstanceControlFlow::EditTasks(Arc::new(merged))
+			}
+			(_, request) => request,
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::InstanceControlFlow;
+
+	use std::{path::PathBuf, sync::Arc};
+
+	fn checkpoint(path: &str) -> InstanceControlFlow {
+		InstanceControlFlow::Checkpoint(
+			Arc::new(PathBuf::from(path)),
+			Box::new(InstanceControlFlow::Continue),
+		)
+	}
+
+	#[test]
+	fn checkpoints_keep_other_requests() {
+		let mut control_flow = InstanceControlFlow::Continue;
+		control_flow.merge(InstanceControlFlow::Stop);
+		control_flow.merge(checkpoint("a"));
+		control_flow.merge(checkpoint("b"));
+		match &control_flow {
+			InstanceControlFlow::Checkpoint(path, then) => {
+				assert_eq!(**path, PathBuf::from("b"));
+				assert!(matches!(**then, InstanceControlFlow::Stop));
+			}
+			_ => panic!("expected a checkpoint"),
+		}
+
+		control_flow.merge(InstanceControlFlow::FullReload);
+		assert!(matches!(
+			control_flow,
+			InstanceControlFlow::Checkpoint(_, then)
+				if matches!(*then, InstanceControlFlow::FullReload)
+		));
+	}
+}
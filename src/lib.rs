@@ -1,3 +1,4 @@
+#![cfg_attr(test, feature(test))]
 #![feature(arbitrary_self_types)]
 #![feature(negative_impls)]
 #![feature(path_try_exists)]
@@ -8,8 +9,10 @@ pub mod concurrency;
 pub mod dylib_management;
 pub mod errors;
 pub mod identify;
+pub mod recording;
 pub mod user_types;
 pub mod utils;
 
 pub mod custard_instance;
 pub mod instance_control_flow;
+pub mod run_options;
@@ -0,0 +1,243 @@
+use crate::{
+	composition::loaded::loaded_crate::LoadedCrate,
+	errors::custard_error::CustardError,
+	identify::{
+		crate_name::CrateName, custard_name::CustardName,
+		datachunk_name::FullDatachunkName, task_name::FullTaskName,
+	},
+	recording::datachunk_log::{read_string, read_u32, write_str},
+};
+
+use log::{info, warn};
+
+use std::{
+	collections::BTreeMap,
+	fs::{self, File},
+	io::{self, BufReader, BufWriter, Read, Write},
+	path::Path,
+};
+
+const MAGIC: &[u8; 8] = b"CUSTCKP1";
+
+/// The saved state of every datachunk and task that implements `save_state`, taken while no task is running.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Checkpoint {
+	pub datachunks: BTreeMap<FullDatachunkName, String>,
+	pub tasks: BTreeMap<FullTaskName, String>,
+}
+
+impl Checkpoint {
+	/// Save the state of everything in `crates`. Must only be called while every fulfiller has ceased.
+	pub(crate) fn capture(crates: &BTreeMap<CrateName, LoadedCrate>) -> Self {
+		let mut ret = Self::default();
+		for (crate_name, crate_contents) in crates {
+			for (datachunk_name, loaded_datachunk) in &crate_contents.datachunks
+			{
+				let state = match loaded_datachunk {
+					Some(v) => v.user_data.get().save_state(),
+					None => continue,
+				};
+				if let Some(state) = state {
+					ret.datachunks.insert(
+						FullDatachunkName {
+							crate_name: crate_name.clone(),
+							datachunk_name: datachunk_name.clone(),
+						},
+						state,
+					);
+				}
+			}
+			for (task_name, fulfiller) in &crate_contents.tasks {
+				let state = match &fulfiller.task {
+					Some(v) => v.user_data.lock().save_state(),
+					None => continue,
+				};
+				if let Some(state) = state {
+					ret.tasks.insert(
+						FullTaskName {
+							crate_name: crate_name.clone(),
+							task_name: task_name.clone(),
+						},
+						state,
+					);
+				}
+			}
+		}
+		ret
+	}
+
+	/// Restore the saved states into `crates`. States of datachunks and tasks that are no longer in the composition are skipped. Must only be called while every fulfiller has ceased.
+	pub(crate) fn restore(
+		&self,
+		crates: &BTreeMap<CrateName, LoadedCrate>,
+	) -> Result<(), CustardError> {
+		for (name, state) in &self.datachunks {
+			let loaded_datachunk = match crates
+				.get(&name.crate_name)
+				.and_then(|v| v.datachunks.get(&name.datachunk_name))
+				.and_then(|v| v.as_ref())
+			{
+				Some(v) => v,
+				None => {
+					warn!("Checkpointed datachunk {:?} is not loaded.", name);
+					continue;
+				}
+			};
+			unsafe { loaded_datachunk.user_data.get_mut() }
+				.restore_state(state)
+				.map_err(|e| CustardError::Restore {
+					name: format!(
+						"datachunk {}::{}",
+						name.crate_name.get(),
+						name.datachunk_name.get()
+					),
+					source: e,
+				})?;
+		}
+		for (name, state) in &self.tasks {
+			let loaded_task = match crates
+				.get(&name.crate_name)
+				.and_then(|v| v.tasks.get(&name.task_name))
+				.and_then(|v| v.task.as_ref())
+			{
+				Some(v) => v,
+				None => {
+					warn!("Checkpointed task {:?} is not loaded.", name);
+					continue;
+				}
+			};
+			loaded_task
+				.user_data
+				.lock()
+				.restore_state(state)
+				.map_err(|e| CustardError::Restore {
+					name: format!(
+						"task {}::{}",
+						name.crate_name.get(),
+						name.task_name.get()
+					),
+					source: e,
+				})?;
+		}
+		Ok(())
+	}
+
+	pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+		writer.write_all(MAGIC)?;
+		writer.write_all(&(self.datachunks.len() as u32).to_le_bytes())?;
+		for (name, state) in &self.datachunks {
+			write_str(&mut writer, name.crate_name.get())?;
+			write_str(&mut writer, name.datachunk_name.get())?;
+			write_str(&mut writer, state)?;
+		}
+		writer.write_all(&(self.tasks.len() as u32).to_le_bytes())?;
+		for (name, state) in &self.tasks {
+			write_str(&mut writer, name.crate_name.get())?;
+			write_str(&mut writer, name.task_name.get())?;
+			write_str(&mut writer, state)?;
+		}
+		writer.flush()
+	}
+
+	pub fn read(mut reader: impl Read) -> io::Result<Self> {
+		let mut magic = [0; 8];
+		reader.read_exact(&mut magic)?;
+		if &magic != MAGIC {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"not a checkpoint",
+			));
+		}
+		let mut ret = Self::default();
+		for _ in 0..read_u32(&mut reader)? {
+			ret.datachunks.insert(
+				FullDatachunkName::new(
+					read_string(&mut reader)?.unwrap_or_default(),
+					read_string(&mut reader)?.unwrap_or_default(),
+				),
+				read_string(&mut reader)?.unwrap_or_default(),
+			);
+		}
+		for _ in 0..read_u32(&mut reader)? {
+			ret.tasks.insert(
+				FullTaskName::new(
+					read_string(&mut reader)?.unwrap_or_default(),
+					read_string(&mut reader)?.unwrap_or_default(),
+				),
+				read_string(&mut reader)?.unwrap_or_default(),
+			);
+		}
+		Ok(ret)
+	}
+
+	/// Write the checkpoint to `path`. It is written to a temporary file and synced to disk first, so a crash while saving leaves the previous checkpoint intact, and the rename is synced before this returns.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CustardError> {
+		let path = path.as_ref();
+		let file_error = |e: io::Error| CustardError::File {
+			path: path.display().to_string(),
+			source: Box::new(e),
+		};
+		let temp_path = path.with_extension("partial");
+		let mut writer =
+			BufWriter::new(File::create(&temp_path).map_err(file_error)?);
+		self.write(&mut writer).map_err(file_error)?;
+		writer.get_ref().sync_all().map_err(file_error)?;
+		drop(writer);
+		fs::rename(&temp_path, path).map_err(file_error)?;
+		let dir = match path.parent() {
+			Some(v) if !v.as_os_str().is_empty() => v,
+			_ => Path::new("."),
+		};
+		File::open(dir)
+			.and_then(|v| v.sync_all())
+			.map_err(file_error)?;
+		info!(
+			"Saved checkpoint of {} datachunks and {} tasks to {}",
+			self.datachunks.len(),
+			self.tasks.len(),
+			path.display()
+		);
+		Ok(())
+	}
+
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, CustardError> {
+		let path = path.as_ref();
+		let file_error = |e: io::Error| CustardError::File {
+			path: path.display().to_string(),
+			source: Box::new(e),
+		};
+		Self::read(BufReader::new(File::open(path).map_err(file_error)?))
+			.map_err(file_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Checkpoint;
+
+	use crate::identify::{
+		datachunk_name::FullDatachunkName, task_name::FullTaskName,
+	};
+
+	#[test]
+	fn checkpoints_round_trip() {
+		let mut checkpoint = Checkpoint::default();
+		checkpoint.datachunks.insert(
+			FullDatachunkName::new("sim".to_owned(), "bodies".to_owned()),
+			"[(x: 1.0, v: 0.5)]".to_owned(),
+		);
+		checkpoint.datachunks.insert(
+			FullDatachunkName::new("sim".to_owned(), "clock".to_owned()),
+			"".to_owned(),
+		);
+		checkpoint.tasks.insert(
+			FullTaskName::new("sim".to_owned(), "step".to_owned()),
+			"(steps: 4000)".to_owned(),
+		);
+
+		let mut bytes = vec![];
+		checkpoint.write(&mut bytes).unwrap();
+		assert_eq!(Checkpoint::read(bytes.as_slice()).unwrap(), checkpoint);
+		assert!(Checkpoint::read(&bytes[1..]).is_err());
+	}
+}
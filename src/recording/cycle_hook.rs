@@ -0,0 +1,79 @@
+use crate::{
+	composition::{
+		loaded::loaded_crate::LoadedCrate,
+		unloaded::unloaded_composition::UnloadedComposition,
+	},
+	concurrency::access::Access,
+	identify::{
+		crate_name::CrateName, datachunk_name::FullDatachunkName,
+		task_name::FullTaskName,
+	},
+	user_types::datachunk::DatachunkObject,
+	utils::mutable_arc::MutableArc,
+};
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::{self, Debug, Formatter},
+	sync::Arc,
+};
+
+/// Work done once per cycle, at the start of `boundary` and before its closure runs. The hook's accesses are checked as if the boundary task made them, so it can touch datachunks without racing the rest of the graph.
+pub trait CycleHook: Debug + Send + Sync {
+	/// The task whose start is taken as the cycle boundary, usually an entrypoint.
+	fn boundary(&self) -> &FullTaskName;
+
+	fn accesses(&self) -> Vec<Access>;
+
+	/// Tasks that must not run their closure while this hook is attached.
+	fn disabled_tasks(
+		&self,
+		_composition: &UnloadedComposition,
+	) -> BTreeSet<FullTaskName> {
+		BTreeSet::new()
+	}
+
+	fn on_cycle(&self, crates: &BTreeMap<CrateName, LoadedCrate>);
+}
+
+/// Look up a loaded datachunk for a hook, which accesses datachunks by name rather than through a `DatachunkGetter`. Only mutate it if the hook's checked accesses allow it.
+pub(crate) fn find_datachunk(
+	crates: &BTreeMap<CrateName, LoadedCrate>,
+	name: &FullDatachunkName,
+) -> Option<DatachunkObject> {
+	let loaded_datachunk = crates
+		.get(&name.crate_name)?
+		.datachunks
+		.get(&name.datachunk_name)?
+		.as_ref()?;
+	Some(loaded_datachunk.user_data.clone())
+}
+
+/// A hook attached to the fulfiller of its boundary task, together with the crate table it reads datachunks from.
+#[derive(Clone)]
+pub struct BoundCycleHook {
+	hook: Arc<dyn CycleHook>,
+	crate_table: MutableArc<BTreeMap<CrateName, LoadedCrate>>,
+}
+
+impl Debug for BoundCycleHook {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		//the crate table contains this hook's fulfiller
+		f.debug_struct("BoundCycleHook")
+			.field("hook", &self.hook)
+			.finish_non_exhaustive()
+	}
+}
+
+impl BoundCycleHook {
+	pub(crate) fn new(
+		hook: Arc<dyn CycleHook>,
+		crate_table: MutableArc<BTreeMap<CrateName, LoadedCrate>>,
+	) -> Self {
+		Self { hook, crate_table }
+	}
+
+	pub(crate) fn run(&self) {
+		self.hook.on_cycle(self.crate_table.get())
+	}
+}
@@ -0,0 +1,187 @@
+use crate::identify::{
+	custard_name::CustardName, datachunk_name::FullDatachunkName,
+	task_name::FullTaskName,
+};
+
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 8] = b"CUSTLOG1";
+
+/// Length written in place of a state that was not saved.
+const NO_STATE: u32 = u32::MAX;
+
+/// Names stored once at the start of a log, so that each frame only holds states.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatachunkLogHeader {
+	pub boundary: FullTaskName,
+	pub datachunks: Vec<FullDatachunkName>,
+}
+
+/// The states of the header's datachunks at one cycle boundary, in header order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatachunkLogFrame {
+	pub cycle: u64,
+	pub states: Vec<Option<String>>,
+}
+
+pub struct DatachunkLogWriter<W: Write> {
+	inner: W,
+}
+
+impl<W: Write> DatachunkLogWriter<W> {
+	pub fn new(mut inner: W, header: &DatachunkLogHeader) -> io::Result<Self> {
+		inner.write_all(MAGIC)?;
+		write_str(&mut inner, header.boundary.crate_name.get())?;
+		write_str(&mut inner, header.boundary.task_name.get())?;
+		inner.write_all(&(header.datachunks.len() as u32).to_le_bytes())?;
+		for datachunk in &header.datachunks {
+			write_str(&mut inner, datachunk.crate_name.get())?;
+			write_str(&mut inner, datachunk.datachunk_name.get())?;
+		}
+		Ok(Self { inner })
+	}
+
+	/// Write a frame and flush it, so that a crash loses at most the cycle in progress.
+	pub fn write_frame(&mut self, frame: &DatachunkLogFrame) -> io::Result<()> {
+		self.inner.write_all(&frame.cycle.to_le_bytes())?;
+		for state in &frame.states {
+			match state {
+				Some(v) => write_str(&mut self.inner, v)?,
+				None => self.inner.write_all(&NO_STATE.to_le_bytes())?,
+			}
+		}
+		self.inner.flush()
+	}
+}
+
+pub struct DatachunkLogReader<R: Read> {
+	inner: R,
+	header: DatachunkLogHeader,
+}
+
+impl<R: Read> DatachunkLogReader<R> {
+	pub fn new(mut inner: R) -> io::Result<Self> {
+		let mut magic = [0; 8];
+		inner.read_exact(&mut magic)?;
+		if &magic != MAGIC {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"not a datachunk log",
+			));
+		}
+		let boundary = FullTaskName::new(
+			read_string(&mut inner)?.unwrap_or_default(),
+			read_string(&mut inner)?.unwrap_or_default(),
+		);
+		let count = read_u32(&mut inner)?;
+		let mut datachunks = vec![];
+		for _ in 0..count {
+			datachunks.push(FullDatachunkName::new(
+				read_string(&mut inner)?.unwrap_or_default(),
+				read_string(&mut inner)?.unwrap_or_default(),
+			));
+		}
+		Ok(Self {
+			inner,
+			header: DatachunkLogHeader {
+				boundary,
+				datachunks,
+			},
+		})
+	}
+
+	pub fn header(&self) -> &DatachunkLogHeader {
+		&self.header
+	}
+
+	/// The next frame, or `None` at the end of the log.
+	pub fn read_frame(&mut self) -> io::Result<Option<DatachunkLogFrame>> {
+		let mut cycle = [0; 8];
+		match self.inner.read_exact(&mut cycle) {
+			Ok(()) => {}
+			Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+				return Ok(None)
+			}
+			Err(e) => return Err(e),
+		}
+		let mut states = vec![];
+		for _ in 0..self.header.datachunks.len() {
+			states.push(read_string(&mut self.inner)?);
+		}
+		Ok(Some(DatachunkLogFrame {
+			cycle: u64::from_le_bytes(cycle),
+			states,
+		}))
+	}
+}
+
+pub(crate) fn write_str(writer: &mut impl Write, s: &str) -> io::Result<()> {
+	writer.write_all(&(s.len() as u32).to_le_bytes())?;
+	writer.write_all(s.as_bytes())
+}
+
+pub(crate) fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+	let mut bytes = [0; 4];
+	reader.read_exact(&mut bytes)?;
+	Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_string(
+	reader: &mut impl Read,
+) -> io::Result<Option<String>> {
+	let len = read_u32(reader)?;
+	if len == NO_STATE {
+		return Ok(None);
+	}
+	let mut bytes = vec![0; len as usize];
+	reader.read_exact(&mut bytes)?;
+	String::from_utf8(bytes)
+		.map(Some)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		DatachunkLogFrame, DatachunkLogHeader, DatachunkLogReader,
+		DatachunkLogWriter,
+	};
+
+	use crate::identify::{
+		datachunk_name::FullDatachunkName, task_name::FullTaskName,
+	};
+
+	#[test]
+	fn frames_round_trip() {
+		let header = DatachunkLogHeader {
+			boundary: FullTaskName::new("arm".to_owned(), "tick".to_owned()),
+			datachunks: vec![
+				FullDatachunkName::new("arm".to_owned(), "pose".to_owned()),
+				FullDatachunkName::new("arm".to_owned(), "grip".to_owned()),
+			],
+		};
+		let frames = vec![
+			DatachunkLogFrame {
+				cycle: 0,
+				states: vec![Some("(x: 1.0)".to_owned()), None],
+			},
+			DatachunkLogFrame {
+				cycle: 1,
+				states: vec![Some("(x: 2.5)".to_owned()), Some("".to_owned())],
+			},
+		];
+
+		let mut bytes = vec![];
+		let mut writer = DatachunkLogWriter::new(&mut bytes, &header).unwrap();
+		for frame in &frames {
+			writer.write_frame(frame).unwrap();
+		}
+
+		let mut reader = DatachunkLogReader::new(bytes.as_slice()).unwrap();
+		assert_eq!(reader.header(), &header);
+		for frame in &frames {
+			assert_eq!(reader.read_frame().unwrap().as_ref(), Some(frame));
+		}
+		assert_eq!(reader.read_frame().unwrap(), None);
+	}
+}
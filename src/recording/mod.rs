@@ -0,0 +1,5 @@
+pub mod checkpoint;
+pub mod cycle_hook;
+pub mod datachunk_log;
+pub mod recorder;
+pub mod replayer;
@@ -0,0 +1,115 @@
+use crate::{
+	composition::loaded::loaded_crate::LoadedCrate,
+	concurrency::access::{Access, AccessType},
+	errors::custard_error::CustardError,
+	identify::{
+		crate_name::CrateName, datachunk_name::FullDatachunkName,
+		task_name::FullTaskName,
+	},
+	recording::{
+		cycle_hook::{self, CycleHook},
+		datachunk_log::{
+			DatachunkLogFrame, DatachunkLogHeader, DatachunkLogWriter,
+		},
+	},
+};
+
+use log::{error, warn};
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::{self, Formatter},
+	fs::File,
+	io::BufWriter,
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Mutex,
+	},
+};
+
+/// Writes the state of selected datachunks to a log at every cycle boundary, to be fed back in by a [Replayer](crate::recording::replayer::Replayer).
+pub struct Recorder {
+	header: DatachunkLogHeader,
+	writer: Mutex<DatachunkLogWriter<BufWriter<File>>>,
+	cycle: AtomicU64,
+}
+
+impl fmt::Debug for Recorder {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("Recorder")
+			.field("header", &self.header)
+			.field("cycle", &self.cycle)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Recorder {
+	pub fn new(
+		path: impl AsRef<Path>,
+		boundary: FullTaskName,
+		datachunks: BTreeSet<FullDatachunkName>,
+	) -> Result<Self, CustardError> {
+		let path = path.as_ref();
+		let file_error = |e: std::io::Error| CustardError::File {
+			path: path.display().to_string(),
+			source: Box::new(e),
+		};
+		let header = DatachunkLogHeader {
+			boundary,
+			datachunks: datachunks.into_iter().collect(),
+		};
+		let writer = DatachunkLogWriter::new(
+			BufWriter::new(File::create(path).map_err(file_error)?),
+			&header,
+		)
+		.map_err(file_error)?;
+		Ok(Self {
+			header,
+			writer: Mutex::new(writer),
+			cycle: AtomicU64::new(0),
+		})
+	}
+}
+
+impl CycleHook for Recorder {
+	fn boundary(&self) -> &FullTaskName {
+		&self.header.boundary
+	}
+
+	fn accesses(&self) -> Vec<Access> {
+		self.header
+			.datachunks
+			.iter()
+			.map(|datachunk| Access {
+				of: datachunk.clone(),
+				mut_immut: AccessType::ImmutableAccess,
+				type_name: None,
+				partition: None,
+			})
+			.collect()
+	}
+
+	fn on_cycle(&self, crates: &BTreeMap<CrateName, LoadedCrate>) {
+		let states = self
+			.header
+			.datachunks
+			.iter()
+			.map(|name| {
+				let state = cycle_hook::find_datachunk(crates, name)
+					.and_then(|datachunk| datachunk.get().save_state());
+				if state.is_none() {
+					warn!("Could not record datachunk {:?}.", name);
+				}
+				state
+			})
+			.collect();
+		let frame = DatachunkLogFrame {
+			cycle: self.cycle.fetch_add(1, Ordering::Relaxed),
+			states,
+		};
+		if let Err(e) = self.writer.lock().unwrap().write_frame(&frame) {
+			error!("Could not write recording frame {}: {}", frame.cycle, e);
+		}
+	}
+}
@@ -0,0 +1,142 @@
+use crate::{
+	composition::{
+		loaded::loaded_crate::LoadedCrate,
+		unloaded::unloaded_composition::UnloadedComposition,
+	},
+	concurrency::access::{Access, AccessType},
+	errors::custard_error::CustardError,
+	identify::{crate_name::CrateName, task_name::FullTaskName},
+	recording::{
+		cycle_hook::{self, CycleHook},
+		datachunk_log::{DatachunkLogHeader, DatachunkLogReader},
+	},
+};
+
+use log::{error, info};
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::{self, Formatter},
+	fs::File,
+	io::BufReader,
+	path::Path,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+};
+
+/// Feeds the datachunk states of a [Recorder](crate::recording::recorder::Recorder) log back in at every cycle boundary. Tasks that mutate a replayed datachunk are disabled, so that downstream tasks see exactly the recorded values. Once the log runs out, datachunks keep their last replayed state.
+pub struct Replayer {
+	header: DatachunkLogHeader,
+	reader: Mutex<DatachunkLogReader<BufReader<File>>>,
+	finished: AtomicBool,
+}
+
+impl fmt::Debug for Replayer {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("Replayer")
+			.field("header", &self.header)
+			.field("finished", &self.finished)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Replayer {
+	pub fn new(path: impl AsRef<Path>) -> Result<Self, CustardError> {
+		let path = path.as_ref();
+		let file_error = |e: std::io::Error| CustardError::File {
+			path: path.display().to_string(),
+			source: Box::new(e),
+		};
+		let reader = DatachunkLogReader::new(BufReader::new(
+			File::open(path).map_err(file_error)?,
+		))
+		.map_err(file_error)?;
+		Ok(Self {
+			header: reader.header().clone(),
+			reader: Mutex::new(reader),
+			finished: AtomicBool::new(false),
+		})
+	}
+}
+
+impl CycleHook for Replayer {
+	fn boundary(&self) -> &FullTaskName {
+		&self.header.boundary
+	}
+
+	fn accesses(&self) -> Vec<Access> {
+		self.header
+			.datachunks
+			.iter()
+			.map(|datachunk| Access {
+				of: datachunk.clone(),
+				mut_immut: AccessType::MutableAccess,
+				type_name: None,
+				partition: None,
+			})
+			.collect()
+	}
+
+	fn disabled_tasks(
+		&self,
+		composition: &UnloadedComposition,
+	) -> BTreeSet<FullTaskName> {
+		let mut ret = BTreeSet::new();
+		for (crate_name, crate_contents) in &composition.crates {
+			for (task_name, task_contents) in &crate_contents.tasks {
+				if task_contents.accesses.iter().any(|access| {
+					access.mut_immut == AccessType::MutableAccess
+						&& self.header.datachunks.contains(&access.of)
+				}) {
+					ret.insert(FullTaskName {
+						crate_name: crate_name.clone(),
+						task_name: task_name.clone(),
+					});
+				}
+			}
+		}
+		ret
+	}
+
+	fn on_cycle(&self, crates: &BTreeMap<CrateName, LoadedCrate>) {
+		if self.finished.load(Ordering::Relaxed) {
+			return;
+		}
+		let frame = match self.reader.lock().unwrap().read_frame() {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				info!("Replay finished.");
+				self.finished.store(true, Ordering::Relaxed);
+				return;
+			}
+			Err(e) => {
+				error!("Could not read replay frame: {}", e);
+				self.finished.store(true, Ordering::Relaxed);
+				return;
+			}
+		};
+		for (name, state) in self.header.datachunks.iter().zip(&frame.states) {
+			let state = match state {
+				Some(v) => v,
+				None => continue, //was not recorded
+			};
+			match cycle_hook::find_datachunk(crates, name) {
+				Some(datachunk) => {
+					//the checked mutable access makes this the only reference in use
+					let datachunk = unsafe { datachunk.get_mut() };
+					if let Err(e) = datachunk.restore_state(state) {
+						error!(
+							"Could not replay datachunk {:?} at cycle {}: {}",
+							name, frame.cycle, e
+						);
+					}
+				}
+				None => {
+					error!("Replayed datachunk {:?} is not loaded.", name)
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+/// Limits on how long [CustardInstance::run_with_options](crate::custard_instance::CustardInstance::run_with_options) runs. Once a limit is reached, the next entrypoint to begin a cycle stops the instance instead, as if it had returned `TaskControlFlow::StopAll`, so the cycle in progress always completes.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+	/// Stop after each entrypoint has run this many cycles. Cycles run before a reload or task edit count towards it.
+	pub max_cycles: Option<u64>,
+	pub deadline: Option<Instant>,
+}
+
+impl RunOptions {
+	pub(crate) fn reached(&self, cycles: u64) -> bool {
+		if let Some(max_cycles) = self.max_cycles {
+			if cycles >= max_cycles {
+				return true;
+			}
+		}
+		match self.deadline {
+			Some(v) => Instant::now() >= v,
+			None => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RunOptions;
+
+	use std::time::{Duration, Instant};
+
+	#[test]
+	fn limits_are_reached() {
+		assert!(!RunOptions::default().reached(u64::MAX));
+
+		let cycles = RunOptions {
+			max_cycles: Some(3),
+			..Default::default()
+		};
+		assert!(!cycles.reached(2));
+		assert!(cycles.reached(3));
+
+		let deadline = RunOptions {
+			deadline: Some(Instant::now() - Duration::from_millis(1)),
+			..Default::default()
+		};
+		assert!(deadline.reached(0));
+	}
+}
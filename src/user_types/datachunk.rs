@@ -1,10 +1,54 @@
 use mopa::mopafy;
 
-use std::fmt::Debug;
+use std::{error::Error, fmt::Debug};
 
 use crate::utils::mutable_arc::MutableArc;
 
 pub type DatachunkObject = MutableArc<dyn Datachunkable>;
 
-pub trait Datachunkable: Debug + mopa::Any + Send + Sync {}
+pub trait Datachunkable: Debug + mopa::Any + Send + Sync {
+	/// The full path of the implementing type, used to check loaded datachunks against their composition.
+	fn datachunk_type_name(&self) -> &'static str {
+		std::any::type_name::<Self>()
+	}
+
+	/// Serialize the current state, for recordings and checkpoints. Datachunks that return `None` are skipped.
+	fn save_state(&self) -> Option<String> {
+		None
+	}
+
+	/// Whether this datachunk implements [Partitionable]. Partitionable types must return `true`, as a composition may only list `partitions` for datachunks that do, which is checked when they are loaded.
+	fn is_partitionable(&self) -> bool {
+		false
+	}
+
+	/// Overwrite the current state with one produced by [save_state](Self::save_state).
+	fn restore_state(
+		&mut self,
+		_state: &str,
+	) -> Result<(), Box<dyn Error + Send + Sync>> {
+		Err(format!(
+			"{} does not implement restore_state",
+			self.datachunk_type_name()
+		)
+		.into())
+	}
+}
 mopafy!(Datachunkable);
+
+/// A datachunk that is split into the named `partitions` listed for it in the composition, so that tasks can access each partition separately through [DatachunkGetter::get_partition_mut](crate::composition::loaded::datachunk_getter::DatachunkGetter::get_partition_mut). Implementors must also return `true` from [is_partitionable](Datachunkable::is_partitionable).
+///
+/// # Safety
+/// Tasks that access different partitions run at the same time, each calling `partition_ptr` for its own partition, so no reference to the whole datachunk may exist meanwhile. Implementations must derive the pointer from `this` without creating one, for example with `ptr::addr_of_mut!((*this).field)`, and different names must never resolve to overlapping data.
+pub unsafe trait Partitionable: Datachunkable {
+	type Partition: ?Sized;
+
+	/// Point to the partition named, or return `None` if there is no such partition.
+	///
+	/// # Safety
+	/// `this` points to a live datachunk, parts of which other threads may be accessing through their own partitions.
+	unsafe fn partition_ptr(
+		this: *mut Self,
+		name: &str,
+	) -> Option<*mut Self::Partition>;
+}
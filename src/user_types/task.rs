@@ -10,7 +10,10 @@ use crate::{
 };
 
 use std::{
+	error::Error,
 	fmt::Debug,
+	future::Future,
+	pin::Pin,
 	sync::{Arc, Mutex},
 };
 
@@ -19,19 +22,49 @@ pub type TaskClosureType = Box<TaskClosureTrait<dyn Taskable>>;
 pub type TaskClosureTrait<T> =
 	Mutex<dyn FnMut(Arc<PossiblyPoisonedMutex<T>>) -> TaskClosureOutput + Send>;
 
+pub type TaskFuture = Pin<Box<dyn Future<Output = TaskClosureOutput> + Send>>;
+pub type AsyncTaskClosureType = Box<AsyncTaskClosureTrait<dyn Taskable>>;
+pub type AsyncTaskClosureTrait<T> =
+	Mutex<dyn FnMut(Arc<PossiblyPoisonedMutex<T>>) -> TaskFuture + Send>;
+
+/// The per-cycle closure of a loaded task.
+pub enum TaskClosure {
+	Sync(TaskClosureType),
+	/// Each call returns a future that is driven by the instance's executor. The task counts as complete, and its children are dispatched, once the future resolves.
+	Async(AsyncTaskClosureType),
+}
+
 pub type TaskObject = Arc<PossiblyPoisonedMutex<dyn Taskable>>;
 
 pub trait Taskable: Debug + Send + Sync + mopa::Any {
+	/// Create the closure run once per cycle: [TaskClosure::Sync] for a closure run on the scheduler or the main thread, or [TaskClosure::Async] for one that returns a future, for tasks that wrap async libraries.
 	fn run(
 		&mut self,
 		this_task_name: FullTaskName,
 		datachunk_getter: Arc<DatachunkGetter>,
-	) -> TaskClosureType;
+	) -> TaskClosure;
 	fn handle_control_flow_update(
 		&mut self,
 		this_task_name: &FullTaskName,
 		other_task_name: &FullTaskName,
 		control_flow: &TaskControlFlow,
 	) -> TaskHandlerState;
+
+	/// Serialize the state kept between cycles, for checkpoints. Tasks that return `None` start from their composition state when restored.
+	fn save_state(&self) -> Option<String> {
+		None
+	}
+
+	/// Overwrite the state kept between cycles with one produced by [save_state](Self::save_state). Closures see the restored state as long as they read it from the task they are passed rather than capturing it.
+	fn restore_state(
+		&mut self,
+		_state: &str,
+	) -> Result<(), Box<dyn Error + Send + Sync>> {
+		Err(format!(
+			"{} does not implement restore_state",
+			std::any::type_name::<Self>()
+		)
+		.into())
+	}
 }
 mopafy!(Taskable);
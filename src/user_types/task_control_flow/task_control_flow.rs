@@ -1,13 +1,24 @@
-use crate::identify::crate_name::CrateName;
+use crate::{
+	composition::unloaded::task_edits::TaskEdits,
+	identify::{crate_name::CrateName, task_name::FullTaskName},
+};
 
-use std::{collections::BTreeSet, error::Error, rc::Rc, sync::Arc};
+use std::{
+	collections::BTreeSet, error::Error, path::PathBuf, rc::Rc, sync::Arc,
+};
 
 #[derive(Clone, Debug)]
 pub enum TaskControlFlow {
+	/// Save a checkpoint to the given path at the end of this cycle, once every task has stopped, then carry on. See [CustardInstance::checkpoint](crate::custard_instance::CustardInstance::checkpoint).
+	Checkpoint(Arc<PathBuf>),
 	Continue,
+	/// Add or remove task instances at the end of this cycle. See [TaskEdits].
+	EditTasks(Arc<TaskEdits>),
 	Err(Rc<dyn Error>),
 	FullReload,
 	PartialReload(Arc<BTreeSet<CrateName>>),
+	/// Skip the named children of this task (or all of them, if `None`) for the current cycle only. Skipped tasks do not run their closure, but still release their `Ready` so the graph advances. A child whose parents were all skipped is skipped too, so the skip passes down until it meets a task with a parent that ran. Entrypoints are never skipped, so the next cycle runs as usual.
+	SkipDownstream(Option<Arc<BTreeSet<FullTaskName>>>),
 	StopAll,
 	StopThis,
 }
@@ -0,0 +1,32 @@
+/// Levenshtein distance between two strings, counted in chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	let mut current = vec![0; b.len() + 1];
+
+	for (i, a_char) in a.chars().enumerate() {
+		current[0] = i + 1;
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous[j] + (a_char != *b_char) as usize;
+			current[j + 1] =
+				substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+		}
+		std::mem::swap(&mut previous, &mut current);
+	}
+
+	previous[b.len()]
+}
+
+/// The candidate closest to `target`, if any is close enough to plausibly be a typo of it.
+pub fn closest_match<'a>(
+	target: &str,
+	candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+	let max_distance = (target.chars().count() / 3).max(1);
+	candidates
+		.into_iter()
+		.map(|candidate| (edit_distance(target, candidate), candidate))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
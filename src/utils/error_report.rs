@@ -0,0 +1,13 @@
+use std::error::Error;
+
+/// An error's message followed by those of its sources, separated by `: `. Errors only describe themselves in their message and leave the rest to their sources, so log lines use this to show the whole chain.
+pub fn report(error: &dyn Error) -> String {
+	let mut ret = error.to_string();
+	let mut source = error.source();
+	while let Some(v) = source {
+		ret.push_str(": ");
+		ret.push_str(&v.to_string());
+		source = v.source();
+	}
+	ret
+}
@@ -1,4 +1,7 @@
+pub mod edit_distance;
+pub mod error_report;
 pub mod mutable_arc;
+pub mod panic_capture;
 
 pub mod files;
 pub mod useful_statics;
@@ -25,6 +25,11 @@ where
 		&self.data
 	}
 
+	/// A pointer to the data, for parts of it to be borrowed without borrowing the whole.
+	pub fn as_ptr(&self) -> *mut T {
+		Arc::as_ptr(&self.data) as *mut T
+	}
+
 	pub unsafe fn get_mut(&self) -> &mut T {
 		&mut *(Arc::as_ptr(&self.data) as *mut T)
 	}
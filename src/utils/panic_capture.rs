@@ -0,0 +1,107 @@
+use std::{
+	any::Any,
+	backtrace::Backtrace,
+	cell::{Cell, RefCell},
+	panic::{self, UnwindSafe},
+	sync::Once,
+	thread,
+};
+
+static INSTALL_HOOK: Once = Once::new();
+
+thread_local! {
+	static CAPTURING: Cell<bool> = const { Cell::new(false) };
+	static LAST_PANIC: RefCell<Option<CapturedPanic>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanicLocation {
+	pub file: String,
+	pub line: u32,
+	pub column: u32,
+}
+
+/// What the panic hook saw, to be picked up by whoever catches the unwind on the same thread.
+#[derive(Debug)]
+pub struct CapturedPanic {
+	pub location: Option<PanicLocation>,
+	pub backtrace: Backtrace,
+}
+
+/// Install a panic hook that, inside [catch_unwind], records the location and backtrace of each panic before deferring to the previously installed hook. Panics anywhere else go straight to the previous hook. Only the first call has any effect.
+fn install_hook() {
+	INSTALL_HOOK.call_once(|| {
+		let previous_hook = panic::take_hook();
+		panic::set_hook(Box::new(move |info| {
+			if CAPTURING.with(Cell::get) {
+				let location = info.location().map(|location| PanicLocation {
+					file: location.file().to_owned(),
+					line: location.line(),
+					column: location.column(),
+				});
+				LAST_PANIC.with(|last_panic| {
+					*last_panic.borrow_mut() = Some(CapturedPanic {
+						location,
+						backtrace: Backtrace::force_capture(),
+					});
+				});
+			}
+			previous_hook(info);
+		}));
+	});
+}
+
+/// [panic::catch_unwind] for user code, recording the location and backtrace of a panic for [take_captured]. Only panics raised while a scope like this is running pay for a backtrace.
+pub(crate) fn catch_unwind<R>(
+	f: impl FnOnce() -> R + UnwindSafe,
+) -> thread::Result<R> {
+	install_hook();
+	LAST_PANIC.with(|last_panic| last_panic.borrow_mut().take());
+	let was_capturing = CAPTURING.with(|v| v.replace(true));
+	let ret = panic::catch_unwind(f);
+	CAPTURING.with(|v| v.set(was_capturing));
+	ret
+}
+
+/// Take the panic most recently recorded on this thread by [catch_unwind], if there was one.
+pub(crate) fn take_captured() -> Option<CapturedPanic> {
+	LAST_PANIC.with(|last_panic| last_panic.borrow_mut().take())
+}
+
+/// The message of a panic payload, if it was raised with a string as `panic!` does.
+pub fn payload_message(payload: &(dyn Any + Send)) -> Option<String> {
+	if let Some(v) = payload.downcast_ref::<&'static str>() {
+		return Some((*v).to_owned());
+	}
+	payload.downcast_ref::<String>().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{catch_unwind, payload_message, take_captured};
+
+	use std::panic::{self, AssertUnwindSafe};
+
+	#[test]
+	fn captures_message_and_location() {
+		let mut line = 0;
+		let payload = catch_unwind(AssertUnwindSafe(|| {
+			line = line!() + 1;
+			panic!("task {} failed", 3)
+		}))
+		.unwrap_err();
+		let captured = take_captured().unwrap();
+		assert_eq!(payload_message(&*payload).unwrap(), "task 3 failed");
+		let location = captured.location.unwrap();
+		assert_eq!(location.file, file!());
+		assert_eq!(location.line, line);
+		assert!(take_captured().is_none());
+	}
+
+	#[test]
+	fn ignores_panics_outside_capture_scopes() {
+		assert!(catch_unwind(|| ()).is_ok());
+		assert!(panic::catch_unwind(|| panic!("not a task")).is_err());
+		assert!(take_captured().is_none());
+	}
+}